@@ -36,7 +36,8 @@ contract Deploy is Script {
 
         IVerifier clientProofVerifier = IVerifier(address(new ClientProofVerifier()));
         IVerifier serverProofVerifier = IVerifier(address(new ServerProofVerifier()));
-        Contract circuitContract = new Contract(dkimRegistry, clientProofVerifier, serverProofVerifier);
+        Contract circuitContract =
+            new Contract(dkimRegistry, clientProofVerifier, serverProofVerifier, vm.addr(deployerPrivateKey));
         vm.stopBroadcast();
 
         console.log("Deployed ClientProofVerifier at", address(clientProofVerifier));
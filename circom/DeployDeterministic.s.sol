@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import { console } from "forge-std/console.sol";
+import { Script } from "forge-std/Script.sol";
+import { ClientProofVerifier } from "./tmp/ClientProofVerifier.sol";
+import { ServerProofVerifier } from "./tmp/ServerProofVerifier.sol";
+import { IDKIMRegistry } from "@zk-email/contracts/interfaces/IDKIMRegistry.sol";
+import { Contract, IVerifier } from "./tmp/Contract.sol";
+
+/// Deploys the verifiers and `Contract` through the CREATE2 factory with `CREATE2_SALT`,
+/// skipping contracts that are already deployed.
+contract DeployDeterministic is Script {
+    function run() external {
+        uint256 deployerPrivateKey = vm.envUint("PRIVATE_KEY");
+        if (deployerPrivateKey == 0) {
+            console.log("PRIVATE_KEY env var not set");
+            return;
+        }
+        bytes32 salt = vm.envBytes32("CREATE2_SALT");
+        // A registry deployed with `new` would make the contract address differ per chain
+        address dkimRegistryAddr = vm.envAddress("DKIM_REGISTRY");
+        // Passed explicitly, as `msg.sender` of the constructor is the CREATE2 factory
+        address owner = vm.addr(deployerPrivateKey);
+
+        // Contracts that already have code are skipped, as CREATE2 to an occupied address reverts
+        address clientProofVerifier = computeCreate2Address(salt, hashInitCode(type(ClientProofVerifier).creationCode));
+        address serverProofVerifier = computeCreate2Address(salt, hashInitCode(type(ServerProofVerifier).creationCode));
+        address circuitContract = computeCreate2Address(
+            salt,
+            hashInitCode(
+                type(Contract).creationCode,
+                abi.encode(dkimRegistryAddr, clientProofVerifier, serverProofVerifier, owner)
+            )
+        );
+
+        vm.startBroadcast(deployerPrivateKey);
+        if (clientProofVerifier.code.length == 0) {
+            new ClientProofVerifier{ salt: salt }();
+        }
+        if (serverProofVerifier.code.length == 0) {
+            new ServerProofVerifier{ salt: salt }();
+        }
+        if (circuitContract.code.length == 0) {
+            new Contract{ salt: salt }(
+                IDKIMRegistry(dkimRegistryAddr), IVerifier(clientProofVerifier), IVerifier(serverProofVerifier), owner
+            );
+        }
+        vm.stopBroadcast();
+
+        console.log("Deployed ClientProofVerifier at", clientProofVerifier);
+        console.log("Deployed ServerProofVerifier at", serverProofVerifier);
+        console.log("Deployed Contract at", circuitContract);
+        console.log("Deployed DKIMRegistry at", dkimRegistryAddr);
+        return;
+    }
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import { console } from "forge-std/console.sol";
+import { Script } from "forge-std/Script.sol";
+import { IDKIMRegistry } from "@zk-email/contracts/interfaces/IDKIMRegistry.sol";
+import { DKIMRegistry } from "@zk-email/contracts/DKIMRegistry.sol";
+import { ERC1967Proxy } from "@openzeppelin/contracts/proxy/ERC1967/ERC1967Proxy.sol";
+import { ClientProofVerifier } from "./tmp/ClientProofVerifier.sol";
+import { ServerProofVerifier } from "./tmp/ServerProofVerifier.sol";
+import { Contract, IVerifier } from "./tmp/Contract.sol";
+
+/// Deploys `Contract` behind a UUPS proxy, or upgrades the proxy at `PROXY_ADDRESS`.
+contract DeployUpgradeable is Script {
+    function run() external {
+        uint256 deployerPrivateKey = vm.envUint("PRIVATE_KEY");
+        if (deployerPrivateKey == 0) {
+            console.log("PRIVATE_KEY env var not set");
+            return;
+        }
+        address proxyAddr = vm.envOr("PROXY_ADDRESS", address(0));
+        // msg.sender is forge's default script sender, not the broadcasting key
+        address owner = vm.addr(deployerPrivateKey);
+
+        vm.startBroadcast(deployerPrivateKey);
+        IVerifier clientProofVerifier = IVerifier(address(new ClientProofVerifier()));
+        IVerifier serverProofVerifier = IVerifier(address(new ServerProofVerifier()));
+        Contract implementation = new Contract();
+
+        address dkimRegistryAddr;
+        if (proxyAddr == address(0)) {
+            dkimRegistryAddr = vm.envOr("DKIM_REGISTRY", address(0));
+            if (dkimRegistryAddr == address(0)) {
+                dkimRegistryAddr = address(new DKIMRegistry(owner));
+            }
+            ERC1967Proxy proxy = new ERC1967Proxy(
+                address(implementation),
+                abi.encodeCall(
+                    Contract.initialize,
+                    (IDKIMRegistry(dkimRegistryAddr), clientProofVerifier, serverProofVerifier, owner)
+                )
+            );
+            proxyAddr = address(proxy);
+        } else {
+            // The registry lives in proxy storage and survives the upgrade
+            dkimRegistryAddr = address(Contract(proxyAddr).dkimRegistry());
+            Contract(proxyAddr).upgradeToAndCall(
+                address(implementation),
+                abi.encodeCall(Contract.setVerifiers, (clientProofVerifier, serverProofVerifier))
+            );
+        }
+        vm.stopBroadcast();
+
+        console.log("Deployed ClientProofVerifier at", address(clientProofVerifier));
+        console.log("Deployed ServerProofVerifier at", address(serverProofVerifier));
+        console.log("Deployed ContractImplementation at", address(implementation));
+        if (vm.envOr("PROXY_ADDRESS", address(0)) == address(0)) {
+            console.log("Deployed Contract at", proxyAddr);
+        } else {
+            console.log("Upgraded Contract at", proxyAddr);
+        }
+        console.log("Deployed DKIMRegistry at", dkimRegistryAddr);
+        return;
+    }
+}
@@ -0,0 +1,10 @@
+-- Contracts deployed for each blueprint and chain
+CREATE TABLE IF NOT EXISTS verifier_deployments (
+    blueprint_id UUID NOT NULL REFERENCES blueprints(id) ON DELETE CASCADE,
+    chain_id BIGINT NOT NULL,
+    contract TEXT NOT NULL,
+    address TEXT NOT NULL,
+    tx_hash TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (blueprint_id, chain_id, contract)
+);
@@ -0,0 +1,11 @@
+-- Block explorer verification status of the deployed contracts
+CREATE TABLE IF NOT EXISTS verifier_contract_verifications (
+    blueprint_id UUID NOT NULL REFERENCES blueprints(id) ON DELETE CASCADE,
+    chain_id BIGINT NOT NULL,
+    contract TEXT NOT NULL,
+    explorer TEXT NOT NULL,
+    verified BOOLEAN NOT NULL,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (blueprint_id, chain_id, contract, explorer)
+);
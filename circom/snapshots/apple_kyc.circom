@@ -0,0 +1,61 @@
+pragma circom 2.1.6;
+include "@zk-email/circuits/email-verifier.circom";
+include "@zk-email/circuits/utils/regex.circom";
+include "./regex/Subject_regex.circom";
+template AppleKYC(maxHeaderLength, maxBodyLength, n, k, packSize) {
+    assert(n * k > 2048); // constraints for 2048 bit RSA
+    signal input emailHeader[maxHeaderLength]; // prehashed email data, includes up to 512 + 64 bytes of padding pre SHA256, and padded with lots of 0s at end after the length
+    signal input emailHeaderLength;
+    signal input pubkey[k]; // RSA pubkey, verified with smart contract + DNSSEC proof. Split up into k parts of n bits each.
+    signal input signature[k]; // RSA signature. Split up into k parts of n bits each.
+    signal input proverETHAddress;
+    
+    signal input address[2];
+    
+    // DKIM Verification
+    component EV = EmailVerifier(maxHeaderLength, maxBodyLength, n, k, 1, 0, 0, 1);
+    EV.emailHeader <== emailHeader;
+    EV.emailHeaderLength <== emailHeaderLength;
+    EV.pubkey <== pubkey;
+    EV.signature <== signature;
+    
+    
+    signal output pubkeyHash;
+    pubkeyHash <== EV.pubkeyHash;
+    
+    
+    // Used for nullifier later
+    signal output headerHashHi <== EV.shaHi;
+    signal output headerHashLo <== EV.shaLo;
+    
+    // SUBJECT Extraction
+    signal input SubjectMatchStart;
+    signal input SubjectMatchLength;
+    signal input SubjectCurrentStates[256];
+    signal input SubjectNextStates[256];
+    
+    
+    signal input SubjectCaptureGroup0Id[256];
+    signal input SubjectCaptureGroup0Start[256];
+    
+    signal input SubjectCaptureGroupStartIndices[1];
+    
+    signal SubjectRegexOut, SubjectRegexReveal[20];
+    (SubjectRegexOut, SubjectRegexReveal) <== SubjectRegex(maxHeaderLength, 256)(emailHeader, SubjectMatchStart, SubjectMatchLength, SubjectCurrentStates, SubjectNextStates, SubjectCaptureGroup0Id, SubjectCaptureGroup0Start, SubjectCaptureGroupStartIndices);
+    SubjectRegexOut === 1;
+    
+   
+   var SubjectMaxLength = 256;
+    
+    
+    
+    signal output SubjectPackedOut[computeIntChunkLength(20)];
+    SubjectPackedOut <== PackRegexReveal(20, 20)(SubjectRegexReveal, 0);
+    
+    
+    
+    
+    
+    
+}
+component main { public [proverETHAddress, address] } = AppleKYC(2048, 0, 121, 17, 7);
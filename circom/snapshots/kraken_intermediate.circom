@@ -0,0 +1,85 @@
+pragma circom 2.1.6;
+include "@zk-email/circuits/email-verifier.circom";
+include "@zk-email/circuits/utils/regex.circom";
+include "@zk-email/circuits/utils/hash.circom";
+include "./regex/EmailSubject_regex.circom";
+template krakenintermediate(maxHeaderLength, maxBodyLength, n, k, packSize) {
+    assert(n * k > 2048); // constraints for 2048 bit RSA
+    signal input emailHeader[maxHeaderLength]; // prehashed email data, includes up to 512 + 64 bytes of padding pre SHA256, and padded with lots of 0s at end after the length
+    signal input emailHeaderLength;
+    signal input pubkey[k]; // RSA pubkey, verified with smart contract + DNSSEC proof. Split up into k parts of n bits each.
+    signal input signature[k]; // RSA signature. Split up into k parts of n bits each.
+    signal input proverETHAddress;
+    
+    signal input test[133];
+    
+    // DKIM Verification
+    component EV = EmailVerifier(maxHeaderLength, maxBodyLength, n, k, 1, 0, 0, 0);
+    EV.emailHeader <== emailHeader;
+    EV.emailHeaderLength <== emailHeaderLength;
+    EV.pubkey <== pubkey;
+    EV.signature <== signature;
+    
+    
+    signal output pubkeyHash;
+    pubkeyHash <== EV.pubkeyHash;
+    
+    
+    // Used for nullifier later
+    signal output headerHashHi <== EV.shaHi;
+    signal output headerHashLo <== EV.shaLo;
+    
+    // EMAILSUBJECT Extraction
+    signal input EmailSubjectMatchStart;
+    signal input EmailSubjectMatchLength;
+    signal input EmailSubjectCurrentStates[64];
+    signal input EmailSubjectNextStates[64];
+    
+    
+    signal input EmailSubjectCaptureGroup0Id[64];
+    signal input EmailSubjectCaptureGroup0Start[64];
+    
+    signal input EmailSubjectCaptureGroup1Id[64];
+    signal input EmailSubjectCaptureGroup1Start[64];
+    
+    signal input EmailSubjectCaptureGroupStartIndices[2];
+    
+    signal EmailSubjectRegexOut, EmailSubjectRegexReveal[20], EmailSubjectRegexReveal1[20];
+    (EmailSubjectRegexOut, EmailSubjectRegexReveal, EmailSubjectRegexReveal1) <== EmailSubjectRegex(maxHeaderLength, 64)(emailHeader, EmailSubjectMatchStart, EmailSubjectMatchLength, EmailSubjectCurrentStates, EmailSubjectNextStates, EmailSubjectCaptureGroup0Id, EmailSubjectCaptureGroup0Start, EmailSubjectCaptureGroupStartIndices);
+    EmailSubjectRegexOut === 1;
+    
+   
+   var EmailSubjectMaxLength = 64;
+    
+    var EmailSubjectTotalPackedLength = computeIntChunkLength(EmailSubjectMaxLength) * 2;
+    signal EmailSubjectAllPackedOut[EmailSubjectTotalPackedLength];
+    
+    
+    
+    signal EmailSubjectPackedOut[computeIntChunkLength(20)];
+    EmailSubjectPackedOut <== PackRegexReveal(20, 20)(EmailSubjectRegexReveal, 0);
+    
+    for (var j = 0; j < computeIntChunkLength(EmailSubjectMaxLength); j++) {
+        EmailSubjectAllPackedOut[j] <== EmailSubjectPackedOut[j];
+    }
+    
+    
+    
+    
+    signal EmailSubjectPackedOut1[computeIntChunkLength(20)];
+    EmailSubjectPackedOut1 <== PackRegexReveal(20, 20)(EmailSubjectRegexReveal1, 0);
+    
+    for (var j = 0; j < computeIntChunkLength(EmailSubjectMaxLength); j++) {
+        EmailSubjectAllPackedOut[j + (1 * computeIntChunkLength(EmailSubjectMaxLength))] <== EmailSubjectPackedOut1[j];
+    }
+    
+    
+    
+    
+    signal output EmailSubjectPackedHash;
+    EmailSubjectPackedHash <== PoseidonModular(EmailSubjectTotalPackedLength)(EmailSubjectAllPackedOut);
+    
+    
+    
+}
+component main { public [proverETHAddress, test] } = krakenintermediate(1088, 0, 121, 17, 7);
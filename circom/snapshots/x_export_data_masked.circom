@@ -0,0 +1,100 @@
+pragma circom 2.1.6;
+include "@zk-email/circuits/email-verifier.circom";
+include "@zk-email/circuits/utils/regex.circom";
+include "./regex/downloadDataLink_regex.circom";
+template XAccountExportData(maxHeaderLength, maxBodyLength, n, k, packSize) {
+    assert(n * k > 2048); // constraints for 2048 bit RSA
+    signal input emailHeader[maxHeaderLength]; // prehashed email data, includes up to 512 + 64 bytes of padding pre SHA256, and padded with lots of 0s at end after the length
+    signal input emailHeaderLength;
+    signal input pubkey[k]; // RSA pubkey, verified with smart contract + DNSSEC proof. Split up into k parts of n bits each.
+    signal input signature[k]; // RSA signature. Split up into k parts of n bits each.
+    signal input proverETHAddress;
+    
+    // DKIM Verification
+    component EV = EmailVerifier(maxHeaderLength, maxBodyLength, n, k, 0, 1, 1, 1);
+    EV.emailHeader <== emailHeader;
+    EV.emailHeaderLength <== emailHeaderLength;
+    EV.pubkey <== pubkey;
+    EV.signature <== signature;
+    
+    signal input bodyHashIndex;
+    signal input bodyHashMatchStart;
+    signal input bodyHashMatchLength;
+    signal input bodyHashCurrStates[maxHeaderLength-1];
+    signal input bodyHashNextStates[maxHeaderLength-1];
+    signal input bodyHashCaptureGroup1Id[maxHeaderLength-1];
+    signal input bodyHashCaptureGroup1Start[maxHeaderLength-1];
+    signal input bodyHashCaptureGroupStartIndices[1];
+    signal input precomputedSHA[32];
+    signal input emailBody[maxBodyLength];
+    signal input emailBodyLength;
+    EV.bodyHashIndex <== bodyHashIndex;
+    EV.bodyHashMatchStart <== bodyHashMatchStart;
+    EV.bodyHashMatchLength <== bodyHashMatchLength;
+    EV.bodyHashCurrStates <== bodyHashCurrStates;
+    EV.bodyHashNextStates <== bodyHashNextStates;
+    EV.bodyHashCaptureGroup1Id <== bodyHashCaptureGroup1Id;
+    EV.bodyHashCaptureGroup1Start <== bodyHashCaptureGroup1Start;
+    EV.bodyHashCaptureGroupStartIndices <== bodyHashCaptureGroupStartIndices;
+    EV.precomputedSHA <== precomputedSHA;
+    EV.emailBody <== emailBody;
+    EV.emailBodyLength <== emailBodyLength;
+    
+    signal input decodedEmailBodyIn[maxBodyLength];
+    EV.decodedEmailBodyIn <== decodedEmailBodyIn;
+    
+    
+    signal input bodyMask[maxBodyLength];
+    signal output maskedBody[maxBodyLength];
+    EV.bodyMask <== bodyMask;
+    
+    
+    
+    signal input headerMask[maxHeaderLength];
+    signal output maskedHeader[maxHeaderLength];
+    
+    EV.headerMask <== headerMask;
+    
+    signal output pubkeyHash;
+    pubkeyHash <== EV.pubkeyHash;
+    
+    maskedHeader <== EV.maskedHeader;
+    
+    
+    maskedBody <== EV.maskedBody;
+    
+    // Used for nullifier later
+    signal output headerHashHi <== EV.shaHi;
+    signal output headerHashLo <== EV.shaLo;
+    
+    // DOWNLOADDATALINK Extraction
+    signal input downloadDataLinkMatchStart;
+    signal input downloadDataLinkMatchLength;
+    signal input downloadDataLinkCurrentStates[128];
+    signal input downloadDataLinkNextStates[128];
+    
+    
+    signal input downloadDataLinkCaptureGroup0Id[128];
+    signal input downloadDataLinkCaptureGroup0Start[128];
+    
+    signal input downloadDataLinkCaptureGroupStartIndices[1];
+    
+    signal downloadDataLinkRegexOut, downloadDataLinkRegexReveal[20];
+    (downloadDataLinkRegexOut, downloadDataLinkRegexReveal) <== downloadDataLinkRegex(maxBodyLength, 128)(decodedEmailBodyIn, downloadDataLinkMatchStart, downloadDataLinkMatchLength, downloadDataLinkCurrentStates, downloadDataLinkNextStates, downloadDataLinkCaptureGroup0Id, downloadDataLinkCaptureGroup0Start, downloadDataLinkCaptureGroupStartIndices);
+    downloadDataLinkRegexOut === 1;
+    
+   
+   var downloadDataLinkMaxLength = 128;
+    
+    
+    
+    signal output downloadDataLinkPackedOut[computeIntChunkLength(20)];
+    downloadDataLinkPackedOut <== PackRegexReveal(20, 20)(downloadDataLinkRegexReveal, 0);
+    
+    
+    
+    
+    
+    
+}
+component main { public [proverETHAddress] } = XAccountExportData(1024, 6208, 121, 17, 7);
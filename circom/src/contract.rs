@@ -1,22 +1,46 @@
-use std::{collections::HashMap, env, fs, path::Path};
+use std::{env, fs, path::Path};
 
 use anyhow::Result;
 use regex::Regex;
 use relayer_utils::LOG;
-use sdk_utils::{run_command, run_command_and_return_output};
+use sdk_utils::{
+    load_template, proto_types::proto_blueprint::Blueprint, run_command,
+    run_command_and_return_output, run_command_with_env, CircuitPlan, PublicSignalLayout,
+    SignalEncoding, SignalKind,
+};
 use serde::Serialize;
 use slog::info;
 use tera::{Context, Tera};
 
-use crate::payload::Payload;
+use crate::{
+    create2::{
+        blueprint_salt, encode_addresses, has_code, parse_address, predict_addresses,
+        PredictedAddresses, ARTIFACTS_DIR,
+    },
+    deployer::{signer_address, NativeDeployer},
+    deployment::{broadcast_path, Broadcast, DeployedContract, DeploymentResult},
+    payload::{ChainTarget, Payload},
+    verification::{verify_contracts, ContractToVerify},
+};
+
+pub const CONTRACT_TEMPLATE_FILE: &str = "template.sol.tera";
+/// Contract template compiled into the binary, overridable through `$TEMPLATES_DIR`.
+pub const CONTRACT_TEMPLATE: &str = include_str!("../templates/template.sol.tera");
 
 #[derive(Serialize)]
 pub struct ContractData {
     pub sender_domain: String,
+    /// Domains accepted besides `sender_domain`, without duplicates.
+    pub additional_sender_domains: Vec<String>,
     pub values: Vec<Field>,
     pub external_inputs: Vec<Field>,
     pub signal_size: usize,
+    pub pubkey_hash_idx: usize,
     pub prover_eth_address_idx: usize,
+    pub nullifier_idx: Option<usize>,
+    pub enforce_unique_nullifier: bool,
+    /// Whether the contract is deployed behind a UUPS proxy and initialized instead of constructed.
+    pub upgradeable: bool,
 }
 
 #[derive(Serialize)]
@@ -25,18 +49,58 @@ pub struct Field {
     pub max_length: usize,
     pub pack_size: usize,
     pub start_idx: usize,
+    /// Whether the signal is a Poseidon commitment to a private external input.
+    pub is_committed: bool,
+    /// Solidity type the signal decodes to.
+    pub sol_type: &'static str,
 }
 
-pub fn create_contract(contract_data: &ContractData) -> Result<()> {
+/// Maps the encoding of a public signal to the Solidity type it decodes to.
+fn solidity_type(encoding: SignalEncoding) -> &'static str {
+    match encoding {
+        SignalEncoding::Address => "address",
+        SignalEncoding::Bytes32 => "bytes32",
+        SignalEncoding::Bool => "bool",
+        SignalEncoding::Bytes | SignalEncoding::PackedBytes | SignalEncoding::BoundedBytes => {
+            "string"
+        }
+        SignalEncoding::Field | SignalEncoding::PoseidonHash | SignalEncoding::PedersenHash => {
+            "uint256"
+        }
+    }
+}
+
+/// Renders `Contract.sol` into `out_dir`.
+pub fn create_contract(contract_data: &ContractData, out_dir: &Path) -> Result<()> {
     // Initialize Tera
     let mut tera = Tera::default();
-    tera.add_template_file("./templates/template.sol.tera", Some("Contract.sol"))?;
+    tera.add_raw_template(
+        "Contract.sol",
+        &load_template(CONTRACT_TEMPLATE_FILE, CONTRACT_TEMPLATE)?,
+    )?;
 
     let mut context = Context::new();
     context.insert("sender_domain", &contract_data.sender_domain);
+    context.insert(
+        "additional_sender_domains",
+        &contract_data.additional_sender_domains,
+    );
     context.insert("values", &contract_data.values);
     context.insert("external_inputs", &contract_data.external_inputs);
+    let fields: Vec<&Field> = contract_data
+        .values
+        .iter()
+        .chain(&contract_data.external_inputs)
+        .collect();
+    context.insert("fields", &fields);
     context.insert("signal_size", &contract_data.signal_size);
+    context.insert("pubkey_hash_idx", &contract_data.pubkey_hash_idx);
+    context.insert("nullifier_idx", &contract_data.nullifier_idx);
+    context.insert(
+        "enforce_unique_nullifier",
+        &contract_data.enforce_unique_nullifier,
+    );
+    context.insert("upgradeable", &contract_data.upgradeable);
     context.insert(
         "prover_eth_address_idx",
         &contract_data.prover_eth_address_idx,
@@ -49,61 +113,60 @@ pub fn create_contract(contract_data: &ContractData) -> Result<()> {
     let cleaned_contract = re.replace_all(&rendered_contract, "\n").to_string();
 
     // Write the rendered template to a file
-    std::fs::write("tmp/Contract.sol", cleaned_contract)?;
+    std::fs::write(out_dir.join("Contract.sol"), cleaned_contract)?;
 
     Ok(())
 }
 
-pub fn prepare_contract_data(payload: &Payload) -> ContractData {
-    let mut signal_size = 1 + 1 + 2; // For pubkey, proverETHAddress and sha256 hash of header
-    let mut current_idx = 1;
+/// Derives the contract constants from the public signal layout of the circuit, so the
+/// indices the contract reads always match the circuit outputs.
+pub fn prepare_contract_data(blueprint: &Blueprint) -> ContractData {
+    let layout = PublicSignalLayout::circom(&CircuitPlan::from(blueprint));
 
     let mut values = Vec::new();
-    for regex in &payload.blueprint.decomposed_regexes {
-        let pack_size = ((regex.max_match_length as f64) / 31.0).ceil() as usize;
-        let field = Field {
-            name: regex.name.clone(),
-            max_length: regex.max_match_length as usize,
-            pack_size,
-            start_idx: current_idx,
+    let mut external_inputs = Vec::new();
+    let mut pubkey_hash_idx = 0;
+    let mut prover_eth_address_idx = 0;
+    let mut nullifier_idx = None;
+    for signal in &layout.signals {
+        let field = || Field {
+            name: signal.name.clone(),
+            max_length: signal.max_length.unwrap_or_default(),
+            pack_size: signal.width,
+            start_idx: signal.start_idx,
+            is_committed: matches!(signal.kind, SignalKind::ExternalInputCommitment { .. }),
+            sol_type: solidity_type(signal.encoding),
         };
-        for part in regex.parts.iter() {
-            if part.is_public == Some(true) {
-                if regex.is_hashed.unwrap_or(false) {
-                    signal_size += 1;
-                    current_idx += 1;
-                } else {
-                    signal_size += pack_size;
-                    current_idx += pack_size;
-                }
+        match &signal.kind {
+            SignalKind::PubkeyHash => pubkey_hash_idx = signal.start_idx,
+            SignalKind::ProverAddress => prover_eth_address_idx = signal.start_idx,
+            SignalKind::EmailNullifier { .. } => nullifier_idx = Some(signal.start_idx),
+            SignalKind::RegexCapture { .. } | SignalKind::RegexHash { .. } => values.push(field()),
+            SignalKind::ExternalInput | SignalKind::ExternalInputCommitment { .. } => {
+                external_inputs.push(field())
             }
+            _ => {}
         }
-        values.push(field);
     }
 
-    let prover_eth_address_idx = current_idx;
-    current_idx += 1; // Add 1 prover ETH address
-
-    let mut external_inputs = Vec::new();
-    for input in &payload.blueprint.external_inputs {
-        let pack_size = ((input.max_length as f64) / 31.0).ceil() as usize;
-        let field = Field {
-            name: input.name.clone(),
-            max_length: input.max_length as usize,
-            pack_size,
-            start_idx: current_idx,
-        };
-        signal_size += pack_size;
-        current_idx += pack_size;
-        external_inputs.push(field);
+    let mut additional_sender_domains: Vec<String> = Vec::new();
+    for domain in &blueprint.additional_sender_domains {
+        if *domain != blueprint.sender_domain && !additional_sender_domains.contains(domain) {
+            additional_sender_domains.push(domain.clone());
+        }
     }
 
     ContractData {
-        sender_domain: payload.blueprint.sender_domain.clone(),
+        sender_domain: blueprint.sender_domain.clone(),
+        additional_sender_domains,
         values,
         external_inputs,
-        signal_size,
+        signal_size: layout.len(),
+        pubkey_hash_idx,
         prover_eth_address_idx,
+        nullifier_idx,
+        enforce_unique_nullifier: blueprint.enforce_unique_nullifier.unwrap_or(false),
+        upgradeable: false,
     }
 }
 
@@ -172,185 +235,501 @@ pub async fn generate_verifier_contract(
     Ok(())
 }
 
-pub async fn deploy_verifier_contract(payload: Payload) -> Result<String> {
-    info!(LOG, "Building contracts");
-    run_command("yarn", &["build"], None).await?;
+/// Storage slot holding the implementation address of an ERC-1967 proxy.
+const ERC1967_IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
 
-    info!(LOG, "Deploying contracts");
-    let output = run_command_and_return_output("yarn", &["deploy"], None).await?;
-
-    // Parse the output to extract addresses
-    let re = Regex::new(
-        r"Deployed (ClientProofVerifier|ServerProofVerifier|Contract|DKIMRegistry) at (0x[a-fA-F0-9]{40})"
-    ).unwrap();
-    let mut contract_addresses = HashMap::new();
-    for cap in re.captures_iter(&output) {
-        let contract_name = &cap[1];
-        let address = &cap[2];
-        contract_addresses.insert(contract_name.to_string(), address.to_string());
-        info!(LOG, "Deployed {} at address: {}", contract_name, address);
+/// Returns the implementation address stored in an ERC-1967 implementation slot, or `None`
+/// if the slot is empty and the contract is not a proxy.
+fn implementation_from_slot(word: &str) -> Option<String> {
+    let word = word.trim().trim_start_matches("0x");
+    if word.len() != 64 || !word.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    if word.chars().all(|c| c == '0') {
+        return None;
     }
+    Some(format!("0x{}", &word[24..]))
+}
+
+/// Returns the proxy previously deployed for the blueprint on the target chain, if any.
+/// `recorded_contract` comes from `verifier_deployments`; blueprints deployed before
+/// deployments were recorded per chain fall back to the blueprint address.
+async fn find_existing_proxy(
+    payload: &Payload,
+    target: &ChainTarget,
+    recorded_contract: Option<&str>,
+) -> Result<Option<String>> {
+    let blueprint = &payload.blueprint;
+    let address = match recorded_contract {
+        Some(address) => address,
+        None if !blueprint.verifier_contract_address.is_empty()
+            && blueprint.verifier_contract_chain == target.chain_id as i32 =>
+        {
+            &blueprint.verifier_contract_address
+        }
+        None => return Ok(None),
+    };
 
-    // Write constructor arguments to a file
-    info!(LOG, "Writing constructor arguments to a file");
-    let constructor_args = run_command_and_return_output(
+    let word = run_command_and_return_output(
         "cast",
         &[
-            "abi-encode",
-            "constructor(address,address,address)",
-            contract_addresses.get("DKIMRegistry").unwrap(),
-            contract_addresses.get("ClientProofVerifier").unwrap(),
-            contract_addresses.get("ServerProofVerifier").unwrap(),
+            "storage",
+            address,
+            ERC1967_IMPLEMENTATION_SLOT,
+            "--rpc-url",
+            &target.rpc_url,
         ],
         None,
     )
     .await?;
-
-    if let Ok(_) = env::var("ETHERSCAN_API_KEY") {
-        info!(LOG, "Verify contracts");
-
-        // Verify ClientProofVerifier with retries
-        let mut last_error = None;
-        for attempt in 1..=3 {
+    match implementation_from_slot(&word) {
+        Some(implementation) => {
             info!(
                 LOG,
-                "Attempting to verify ClientProofVerifier (attempt {}/3)", attempt
+                "Found proxy at {} with implementation {}", address, implementation
             );
-            match run_command(
-                "forge",
-                &[
-                    "verify-contract",
-                    "--chain-id",
-                    payload.chain_id.to_string().as_str(),
-                    contract_addresses.get("ClientProofVerifier").unwrap(),
-                    "tmp/ClientProofVerifier.sol:ClientProofVerifier",
-                ],
-                None,
-            )
-            .await
-            {
-                Ok(_) => {
-                    info!(LOG, "Successfully verified ClientProofVerifier");
-                    last_error = None;
-                    break;
-                }
-                Err(e) => {
-                    info!(
-                        LOG,
-                        "Attempt {}/3 failed to verify ClientProofVerifier: {}", attempt, e
-                    );
-                    last_error = Some(e);
-                    if attempt < 3 {
-                        info!(LOG, "Waiting 10 seconds before retry...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-                    }
-                }
+            Ok(Some(address.to_string()))
+        }
+        None => {
+            info!(LOG, "{} is not a proxy, deploying a new one", address);
+            Ok(None)
+        }
+    }
+}
+
+/// Compiles the generated contracts once for all chains.
+pub async fn build_contracts() -> Result<()> {
+    info!(LOG, "Building contracts");
+    run_command("yarn", &["build"], None).await?;
+    Ok(())
+}
+
+/// Runs the Forge deploy script of the deployment mode and reads its broadcast file.
+async fn deploy_with_forge(
+    payload: &Payload,
+    target: &ChainTarget,
+    recorded_contract: Option<&str>,
+    predicted: Option<&PredictedAddresses>,
+) -> Result<DeploymentResult> {
+    let script = if payload.upgradeable {
+        "DeployUpgradeable.s.sol"
+    } else if payload.deterministic {
+        "DeployDeterministic.s.sol"
+    } else {
+        "Deploy.s.sol"
+    };
+    // A broadcast file left by an earlier run must not be mistaken for this one
+    let broadcast_file = broadcast_path(script, target.chain_id);
+    if broadcast_file.exists() {
+        fs::remove_file(&broadcast_file)?;
+    }
+
+    let existing_proxy = if payload.upgradeable {
+        find_existing_proxy(payload, target, recorded_contract).await?
+    } else {
+        None
+    };
+    // Set on the forge command only, so no chain inherits the proxy or salt of another
+    let salt = format!("0x{}", hex::encode(blueprint_salt(&payload.blueprint)));
+    let (script_command, envs) = if payload.upgradeable {
+        match &existing_proxy {
+            Some(proxy) => info!(LOG, "Upgrading proxy at {}", proxy),
+            None => info!(LOG, "Deploying contracts behind a new proxy"),
+        }
+        (
+            "deploy:upgradeable",
+            vec![("PROXY_ADDRESS", existing_proxy.as_deref())],
+        )
+    } else if payload.deterministic {
+        info!(LOG, "Deploying contracts through the CREATE2 factory");
+        (
+            "deploy:deterministic",
+            vec![("CREATE2_SALT", Some(salt.as_str()))],
+        )
+    } else {
+        info!(LOG, "Deploying contracts");
+        ("deploy", Vec::new())
+    };
+    run_command_with_env("yarn", &[script_command], None, &envs).await?;
+
+    let broadcast = Broadcast::read(&broadcast_file)?;
+    let mut contracts = Vec::new();
+    if let Some(predicted) = predicted {
+        for (name, address) in [
+            ("ClientProofVerifier", &predicted.client_proof_verifier),
+            ("ServerProofVerifier", &predicted.server_proof_verifier),
+            ("Contract", &predicted.contract),
+        ] {
+            // The script skips contracts that already have code at their address
+            let contract = broadcast
+                .find_created(name, name)
+                .unwrap_or_else(|| DeployedContract::existing(name, address));
+            if !contract.address.eq_ignore_ascii_case(address) {
+                return Err(anyhow::anyhow!(
+                    "{} deployed at {}, but {} was predicted",
+                    name,
+                    contract.address,
+                    address
+                ));
             }
+            contracts.push(contract);
         }
-        if let Some(e) = last_error {
-            return Err(anyhow::anyhow!(
-                "Failed to verify ClientProofVerifier after 3 attempts: {}",
-                e
-            ));
+    } else {
+        contracts.push(broadcast.created("ClientProofVerifier", "ClientProofVerifier")?);
+        contracts.push(broadcast.created("ServerProofVerifier", "ServerProofVerifier")?);
+        if payload.upgradeable {
+            contracts.push(broadcast.created("Contract", "ContractImplementation")?);
+            contracts.push(match &existing_proxy {
+                Some(proxy) => broadcast.called(proxy, "Contract")?,
+                None => broadcast.created("ERC1967Proxy", "Contract")?,
+            });
+        } else {
+            contracts.push(broadcast.created("Contract", "Contract")?);
         }
+    }
+    match broadcast.find_created("DKIMRegistry", "DKIMRegistry") {
+        Some(registry) => contracts.push(registry),
+        // An upgraded proxy keeps the registry it was initialized with
+        None if existing_proxy.is_none() && !target.dkim_registry_address.is_empty() => contracts
+            .push(DeployedContract::existing(
+                "DKIMRegistry",
+                &target.dkim_registry_address,
+            )),
+        None => {}
+    }
 
-        // Delay between contract verifications
-        info!(LOG, "Waiting 5 seconds before next verification...");
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    Ok(DeploymentResult {
+        chain_id: target.chain_id,
+        contracts,
+        verifications: Vec::new(),
+    })
+}
+
+/// Deploys the contracts built by `build_contracts` to `target`. `recorded_contract` is the
+/// contract recorded for the blueprint on that chain, the proxy to upgrade in upgradeable mode.
+pub async fn deploy_verifier_contract(
+    payload: &Payload,
+    target: &ChainTarget,
+    recorded_contract: Option<&str>,
+) -> Result<DeploymentResult> {
+    if payload.deterministic && payload.upgradeable {
+        return Err(anyhow::anyhow!(
+            "Deterministic deployment is not supported for upgradeable contracts"
+        ));
+    }
+    if payload.native && (payload.deterministic || payload.upgradeable) {
+        return Err(anyhow::anyhow!(
+            "Native deployment supports neither deterministic nor upgradeable contracts"
+        ));
+    }
+    // Only the Forge deploy script can deploy a new registry
+    if (payload.deterministic || payload.native) && target.dkim_registry_address.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} deployment requires an existing DKIM registry",
+            if payload.native {
+                "Native"
+            } else {
+                "Deterministic"
+            }
+        ));
+    }
 
-        // Verify ServerProofVerifier with retries
-        let mut last_error = None;
-        for attempt in 1..=3 {
+    info!(LOG, "Deploying to chain {}", target.chain_id);
+    target.set_env(&payload.etherscan_api_key);
+    let owner = signer_address(&payload.private_key)?;
+
+    let predicted = if payload.deterministic {
+        let salt = blueprint_salt(&payload.blueprint);
+        let predicted = predict_addresses(
+            Path::new(ARTIFACTS_DIR),
+            &salt,
+            &target.dkim_registry_address,
+            &owner,
+        )?;
+        info!(LOG, "Predicted Contract address: {}", predicted.contract);
+        if has_code(&predicted.contract, &target.rpc_url).await? {
             info!(
                 LOG,
-                "Attempting to verify ServerProofVerifier (attempt {}/3)", attempt
+                "Contract already deployed at {}, skipping deployment", predicted.contract
             );
-            match run_command(
-                "forge",
-                &[
-                    "verify-contract",
-                    "--chain-id",
-                    payload.chain_id.to_string().as_str(),
-                    contract_addresses.get("ServerProofVerifier").unwrap(),
-                    "tmp/ServerProofVerifier.sol:ServerProofVerifier",
+            return Ok(DeploymentResult {
+                chain_id: target.chain_id,
+                contracts: vec![
+                    DeployedContract::existing(
+                        "ClientProofVerifier",
+                        &predicted.client_proof_verifier,
+                    ),
+                    DeployedContract::existing(
+                        "ServerProofVerifier",
+                        &predicted.server_proof_verifier,
+                    ),
+                    DeployedContract::existing("Contract", &predicted.contract),
+                    DeployedContract::existing("DKIMRegistry", &target.dkim_registry_address),
                 ],
-                None,
-            )
-            .await
-            {
-                Ok(_) => {
-                    info!(LOG, "Successfully verified ServerProofVerifier");
-                    last_error = None;
-                    break;
-                }
-                Err(e) => {
-                    info!(
-                        LOG,
-                        "Attempt {}/3 failed to verify ServerProofVerifier: {}", attempt, e
-                    );
-                    last_error = Some(e);
-                    if attempt < 3 {
-                        info!(LOG, "Waiting 10 seconds before retry...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-                    }
-                }
-            }
-        }
-        if let Some(e) = last_error {
-            return Err(anyhow::anyhow!(
-                "Failed to verify ServerProofVerifier after 3 attempts: {}",
-                e
-            ));
+                verifications: Vec::new(),
+            });
         }
+        Some(predicted)
+    } else {
+        None
+    };
+
+    let mut deployment = if payload.native {
+        info!(LOG, "Deploying contracts natively");
+        NativeDeployer::connect(&target.rpc_url, &payload.private_key, target.chain_id)
+            .await?
+            .deploy_contracts(Path::new(ARTIFACTS_DIR), &target.dkim_registry_address)
+            .await?
+    } else {
+        deploy_with_forge(payload, target, recorded_contract, predicted.as_ref()).await?
+    };
+    for contract in &deployment.contracts {
+        info!(
+            LOG,
+            "Deployed {} at address: {}", contract.name, contract.address;
+            "tx_hash" => &contract.tx_hash,
+            "gas_used" => contract.gas_used,
+            "block_number" => contract.block_number
+        );
+    }
 
-        // Delay between contract verifications
-        info!(LOG, "Waiting 5 seconds before next verification...");
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-
-        // Verify Contract with retries
-        let mut last_error = None;
-        for attempt in 1..=3 {
-            info!(LOG, "Attempting to verify Contract (attempt {}/3)", attempt);
-            match run_command(
-                "forge",
-                &[
-                    "verify-contract",
-                    "--chain-id",
-                    payload.chain_id.to_string().as_str(),
-                    "--constructor-args",
-                    &constructor_args,
-                    contract_addresses.get("Contract").unwrap(),
-                    "tmp/Contract.sol:Contract",
-                ],
-                None,
-            )
-            .await
-            {
-                Ok(_) => {
-                    info!(LOG, "Successfully verified Contract");
-                    last_error = None;
-                    break;
-                }
-                Err(e) => {
-                    info!(
-                        LOG,
-                        "Attempt {}/3 failed to verify Contract: {}", attempt, e
-                    );
-                    last_error = Some(e);
-                    if attempt < 3 {
-                        info!(LOG, "Waiting 10 seconds before retry...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-                    }
-                }
-            }
-        }
-        if let Some(e) = last_error {
-            return Err(anyhow::anyhow!(
-                "Failed to verify Contract after 3 attempts: {}",
-                e
-            ));
+    // The implementation behind a proxy has no constructor arguments
+    let (verified_contract, constructor_args) = if payload.upgradeable {
+        (deployment.address("ContractImplementation")?, None)
+    } else {
+        let constructor_args = format!(
+            "0x{}",
+            hex::encode(encode_addresses(&[
+                parse_address(deployment.address("DKIMRegistry")?)?,
+                parse_address(deployment.address("ClientProofVerifier")?)?,
+                parse_address(deployment.address("ServerProofVerifier")?)?,
+                parse_address(&owner)?,
+            ]))
+        );
+        (deployment.contract_address()?, Some(constructor_args))
+    };
+
+    let contracts = [
+        ContractToVerify {
+            name: "ClientProofVerifier".to_string(),
+            address: deployment.address("ClientProofVerifier")?.to_string(),
+            source: "tmp/ClientProofVerifier.sol:ClientProofVerifier".to_string(),
+            constructor_args: None,
+        },
+        ContractToVerify {
+            name: "ServerProofVerifier".to_string(),
+            address: deployment.address("ServerProofVerifier")?.to_string(),
+            source: "tmp/ServerProofVerifier.sol:ServerProofVerifier".to_string(),
+            constructor_args: None,
+        },
+        ContractToVerify {
+            name: "Contract".to_string(),
+            address: verified_contract.to_string(),
+            source: "tmp/Contract.sol:Contract".to_string(),
+            constructor_args,
+        },
+    ];
+    deployment.verifications =
+        verify_contracts(&payload.verification, target.chain_id, &contracts).await;
+
+    Ok(deployment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk_utils::{
+        fixtures,
+        proto_types::proto_blueprint::{ExternalInput, ExternalInputType, ZkFramework},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn render_contract(blueprint: &Blueprint) -> String {
+        render_contract_data(&prepare_contract_data(blueprint), &blueprint.circuit_name)
+    }
+
+    fn render_contract_data(contract_data: &ContractData, circuit_name: &str) -> String {
+        // Tests run in parallel and fixtures share circuit names
+        static RENDERS: AtomicUsize = AtomicUsize::new(0);
+        let out_dir = env::temp_dir().join(format!(
+            "contract-{}-{}-{}",
+            circuit_name,
+            std::process::id(),
+            RENDERS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&out_dir).unwrap();
+        create_contract(contract_data, &out_dir).unwrap();
+        let contract = fs::read_to_string(out_dir.join("Contract.sol")).unwrap();
+        fs::remove_dir_all(out_dir).unwrap();
+        contract
+    }
+
+    #[test]
+    fn test_contract_nullifier() {
+        let mut blueprint = fixtures::subject_extract(ZkFramework::Circom);
+        let contract = render_contract(&blueprint);
+        assert!(!contract.contains("usedNullifiers"));
+        assert!(contract.contains(") external view {"));
+
+        blueprint.enable_nullifier = Some(true);
+        blueprint.enforce_unique_nullifier = Some(true);
+        let contract_data = prepare_contract_data(&blueprint);
+        assert_eq!(contract_data.nullifier_idx, Some(3));
+
+        let contract = render_contract(&blueprint);
+        assert!(contract.contains("uint16 public constant email_nullifier_idx = 3;"));
+        assert!(contract.contains("mapping(uint256 => bool) public usedNullifiers;"));
+        assert!(contract.contains("usedNullifiers[nullifier] = true;"));
+        assert!(contract.contains(") external {"));
+    }
+
+    #[test]
+    fn test_contract_committed_external_input() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        blueprint.external_inputs[0].is_committed = Some(true);
+
+        let contract_data = prepare_contract_data(&blueprint);
+        let commitment = &contract_data.external_inputs[0];
+        assert_eq!(commitment.name, "testCommitment");
+        assert_eq!(commitment.pack_size, 1);
+        assert!(commitment.is_committed);
+        assert_eq!(
+            contract_data.prover_eth_address_idx,
+            commitment.start_idx + 1
+        );
+
+        let contract = render_contract(&blueprint);
+        assert!(contract.contains(&format!(
+            "uint16 public constant testCommitment_idx = {};",
+            commitment.start_idx
+        )));
+    }
+
+    #[test]
+    fn test_contract_decoders() {
+        let blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        let contract_data = prepare_contract_data(&blueprint);
+        let contract = render_contract(&blueprint);
+
+        assert!(
+            contract.contains("        uint256 EmailSubjectPackedHash;\n        string test;\n")
+        );
+        assert!(contract.contains(
+            "event Verified(address indexed prover, ProofType proofType, DecodedSignals signals);"
+        ));
+        assert!(
+            contract.contains("emit Verified(decode_prover(signals), proofType, decode(signals));")
+        );
+        assert!(contract.contains("decoded.test = decode_test(signals);"));
+
+        let test = &contract_data.external_inputs[0];
+        assert!(contract.contains(&format!(
+            "return _unpackString(signals, {}, 133, 4096);",
+            test.start_idx
+        )));
+        assert!(contract.contains(&format!(
+            "return address(uint160(signals[{}]));",
+            contract_data.prover_eth_address_idx
+        )));
+    }
+
+    #[test]
+    fn test_contract_typed_external_inputs() {
+        let mut blueprint = fixtures::subject_extract(ZkFramework::Circom);
+        for (name, input_type) in [
+            ("recipient", ExternalInputType::Address),
+            ("digest", ExternalInputType::Bytes32),
+            ("accepted", ExternalInputType::Bool),
+        ] {
+            blueprint.external_inputs.push(ExternalInput {
+                name: name.to_string(),
+                input_type: Some(input_type as i32),
+                ..Default::default()
+            });
         }
+
+        let contract_data = prepare_contract_data(&blueprint);
+        let types: Vec<&str> = contract_data
+            .external_inputs
+            .iter()
+            .map(|field| field.sol_type)
+            .collect();
+        assert_eq!(types, vec!["address", "bytes32", "bool"]);
+
+        let recipient_idx = contract_data.external_inputs[0].start_idx;
+        let contract = render_contract(&blueprint);
+        assert!(contract.contains(&format!(
+            "return address(uint160(signals[{}]));",
+            recipient_idx
+        )));
+        assert!(contract.contains(&format!(
+            "return bytes32((signals[{}] << 128) | signals[{}]);",
+            recipient_idx + 1,
+            recipient_idx + 2
+        )));
+        assert!(contract.contains(&format!("return signals[{}] == 1;", recipient_idx + 3)));
+    }
+
+    #[test]
+    fn test_contract_additional_sender_domains() {
+        let mut blueprint = fixtures::x_export_data(ZkFramework::Circom);
+        blueprint.additional_sender_domains = vec![
+            "twitter.com".to_string(),
+            "x.com".to_string(),
+            "twitter.com".to_string(),
+        ];
+
+        let contract_data = prepare_contract_data(&blueprint);
+        assert_eq!(contract_data.additional_sender_domains, vec!["twitter.com"]);
+
+        let contract = render_contract(&blueprint);
+        assert!(contract.contains("string public constant domain = \"x.com\";"));
+        assert!(contract.contains("string public constant domain_1 = \"twitter.com\";"));
+        assert!(contract.contains("result = new string[](2);"));
+        assert!(contract.contains("|| dkimRegistry.isDKIMPublicKeyHashValid(domain_1, ph);"));
+        assert!(contract.contains("require(isPubkeyHashValid(ph), \"RSA public key incorrect\");"));
+    }
+
+    #[test]
+    fn test_contract_upgradeable() {
+        let blueprint = fixtures::subject_extract(ZkFramework::Circom);
+        let mut contract_data = prepare_contract_data(&blueprint);
+        contract_data.upgradeable = true;
+        let contract = render_contract_data(&contract_data, &blueprint.circuit_name);
+
+        assert!(contract
+            .contains("contract Contract is Initializable, OwnableUpgradeable, UUPSUpgradeable {"));
+        assert!(contract.contains("    IVerifier public clientProofVerifier;"));
+        assert!(contract.contains("_disableInitializers();"));
+        assert!(contract.contains(") external initializer {"));
+        assert!(
+            contract.contains("function _authorizeUpgrade(address) internal override onlyOwner {}")
+        );
+        assert!(!contract.contains("immutable"));
+        assert!(!contract.contains(") Ownable(initialOwner) {"));
+
+        let contract = render_contract(&blueprint);
+        assert!(contract.contains("contract Contract is Ownable {"));
+        assert!(contract.contains(") Ownable(initialOwner) {"));
+        assert!(contract.contains("IVerifier public immutable clientProofVerifier;"));
+        assert!(!contract.contains("UUPSUpgradeable"));
     }
 
-    Ok(contract_addresses.get("Contract").unwrap().to_string())
+    #[test]
+    fn test_implementation_from_slot() {
+        assert_eq!(
+            implementation_from_slot(
+                "0x0000000000000000000000005fbdb2315678afecb367f032d93f642f64180aa3\n"
+            ),
+            Some("0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string())
+        );
+        assert_eq!(
+            implementation_from_slot(
+                "0x0000000000000000000000000000000000000000000000000000000000000000"
+            ),
+            None
+        );
+        assert_eq!(implementation_from_slot("Error: invalid address"), None);
+    }
 }
@@ -0,0 +1,216 @@
+//! Deterministic deployment through the CREATE2 factory Forge scripts deploy with.
+
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Result};
+use sdk_utils::{proto_types::proto_blueprint::Blueprint, run_command_and_return_output};
+use sha3::{Digest, Keccak256};
+
+/// Deterministic deployment proxy, deployed at the same address on every chain.
+pub const CREATE2_FACTORY: &str = "0x4e59b44847b379578588920cA78FbF26c0B4956C";
+
+/// Directory Forge writes compiled contracts to, see `foundry.toml`.
+pub const ARTIFACTS_DIR: &str = "artifacts";
+
+/// Addresses the verifiers and the contract of a blueprint version deploy to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictedAddresses {
+    pub client_proof_verifier: String,
+    pub server_proof_verifier: String,
+    pub contract: String,
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Derives the CREATE2 salt from the blueprint id and version, so each version gets its
+/// own addresses and deploying a version again resolves to the existing contracts.
+pub fn blueprint_salt(blueprint: &Blueprint) -> [u8; 32] {
+    keccak256(format!("{}:{}", blueprint.id, blueprint.version).as_bytes())
+}
+
+/// Computes the address `deployer` creates `init_code` at with CREATE2.
+pub fn create2_address(deployer: &[u8; 20], salt: &[u8; 32], init_code: &[u8]) -> [u8; 20] {
+    let mut preimage = Vec::with_capacity(85);
+    preimage.push(0xff);
+    preimage.extend_from_slice(deployer);
+    preimage.extend_from_slice(salt);
+    preimage.extend_from_slice(&keccak256(init_code));
+    keccak256(&preimage)[12..].try_into().unwrap()
+}
+
+pub fn parse_address(address: &str) -> Result<[u8; 20]> {
+    let bytes = hex::decode(address.trim().trim_start_matches("0x"))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("Invalid address '{}'", address))
+}
+
+pub fn format_address(address: &[u8; 20]) -> String {
+    format!("0x{}", hex::encode(address))
+}
+
+/// Reads the creation bytecode of `contract` from its Forge artifact.
+pub fn creation_code(artifacts_dir: &Path, contract: &str) -> Result<Vec<u8>> {
+    let path = artifacts_dir
+        .join(format!("{}.sol", contract))
+        .join(format!("{}.json", contract));
+    let artifact: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let bytecode = artifact["bytecode"]["object"]
+        .as_str()
+        .ok_or_else(|| anyhow!("No bytecode in {}", path.display()))?;
+    Ok(hex::decode(bytecode.trim_start_matches("0x"))?)
+}
+
+/// ABI encodes the constructor arguments of `Contract`, which are all addresses.
+pub fn encode_addresses(addresses: &[[u8; 20]]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(addresses.len() * 32);
+    for address in addresses {
+        encoded.extend_from_slice(&[0; 12]);
+        encoded.extend_from_slice(address);
+    }
+    encoded
+}
+
+/// Predicts the addresses `DeployDeterministic.s.sol` deploys to from the compiled artifacts.
+/// The contract address depends on the DKIM registry and the owner, so it only matches across
+/// chains sharing the registry address and deployer key.
+pub fn predict_addresses(
+    artifacts_dir: &Path,
+    salt: &[u8; 32],
+    dkim_registry: &str,
+    owner: &str,
+) -> Result<PredictedAddresses> {
+    let factory = parse_address(CREATE2_FACTORY)?;
+    let client_proof_verifier = create2_address(
+        &factory,
+        salt,
+        &creation_code(artifacts_dir, "ClientProofVerifier")?,
+    );
+    let server_proof_verifier = create2_address(
+        &factory,
+        salt,
+        &creation_code(artifacts_dir, "ServerProofVerifier")?,
+    );
+
+    let mut init_code = creation_code(artifacts_dir, "Contract")?;
+    init_code.extend(encode_addresses(&[
+        parse_address(dkim_registry)?,
+        client_proof_verifier,
+        server_proof_verifier,
+        parse_address(owner)?,
+    ]));
+    let contract = create2_address(&factory, salt, &init_code);
+
+    Ok(PredictedAddresses {
+        client_proof_verifier: format_address(&client_proof_verifier),
+        server_proof_verifier: format_address(&server_proof_verifier),
+        contract: format_address(&contract),
+    })
+}
+
+/// Checks whether a contract is deployed at `address`.
+pub async fn has_code(address: &str, rpc_url: &str) -> Result<bool> {
+    let code =
+        run_command_and_return_output("cast", &["code", address, "--rpc-url", rpc_url], None)
+            .await?;
+    let code = code.trim();
+    Ok(!code.is_empty() && code != "0x")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk_utils::{fixtures, proto_types::proto_blueprint::ZkFramework};
+
+    fn address(address: &str) -> [u8; 20] {
+        parse_address(address).unwrap()
+    }
+
+    #[test]
+    fn test_create2_address() {
+        // Examples from EIP-1014
+        assert_eq!(
+            create2_address(
+                &address("0x0000000000000000000000000000000000000000"),
+                &[0; 32],
+                &[0x00]
+            ),
+            address("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38")
+        );
+        assert_eq!(
+            create2_address(
+                &address("0xdeadbeef00000000000000000000000000000000"),
+                &[0; 32],
+                &[0x00]
+            ),
+            address("0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3")
+        );
+        let mut salt = [0; 32];
+        salt[28..].copy_from_slice(&0xcafebabe_u32.to_be_bytes());
+        assert_eq!(
+            create2_address(
+                &address("0x00000000000000000000000000000000deadbeef"),
+                &salt,
+                &hex::decode("deadbeef").unwrap()
+            ),
+            address("0x60f3f640a8508fC6a86d45DF051962668E1e8AC7")
+        );
+    }
+
+    #[test]
+    fn test_blueprint_salt() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        let salt = blueprint_salt(&blueprint);
+        assert_eq!(
+            salt,
+            blueprint_salt(&fixtures::kraken_intermediate(ZkFramework::Circom))
+        );
+
+        blueprint.version += 1;
+        assert_ne!(salt, blueprint_salt(&blueprint));
+    }
+
+    #[test]
+    fn test_predict_addresses() {
+        let artifacts_dir = std::env::temp_dir().join(format!("artifacts-{}", std::process::id()));
+        for (contract, bytecode) in [
+            ("ClientProofVerifier", "0x6001"),
+            ("ServerProofVerifier", "0x6002"),
+            ("Contract", "0x6003"),
+        ] {
+            let dir = artifacts_dir.join(format!("{}.sol", contract));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(format!("{}.json", contract)),
+                format!(r#"{{"bytecode":{{"object":"{}"}}}}"#, bytecode),
+            )
+            .unwrap();
+        }
+
+        let salt = [7; 32];
+        let registry = "0x3D3935B3C030893f118a84C92C66dF1B9E4169d6";
+        let owner = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+        let predicted = predict_addresses(&artifacts_dir, &salt, registry, owner).unwrap();
+        fs::remove_dir_all(&artifacts_dir).unwrap();
+
+        let factory = address(CREATE2_FACTORY);
+        let client = create2_address(&factory, &salt, &[0x60, 0x01]);
+        let server = create2_address(&factory, &salt, &[0x60, 0x02]);
+        let mut init_code = vec![0x60, 0x03];
+        init_code.extend(encode_addresses(&[
+            address(registry),
+            client,
+            server,
+            address(owner),
+        ]));
+        assert_eq!(predicted.client_proof_verifier, format_address(&client));
+        assert_eq!(predicted.server_proof_verifier, format_address(&server));
+        assert_eq!(
+            predicted.contract,
+            format_address(&create2_address(&factory, &salt, &init_code))
+        );
+        assert_eq!(init_code.len(), 2 + 4 * 32);
+    }
+}
@@ -2,20 +2,25 @@ use anyhow::Result;
 use sqlx::types::Uuid;
 use sqlx::{Pool, Postgres};
 
+use crate::deployment::DeploymentResult;
+
+/// Points the blueprint at the contract deployed on `chain_id`.
 pub async fn update_verifier_contract_address(
     pool: &Pool<Postgres>,
     id: &str,
+    chain_id: u32,
     address: &str,
 ) -> Result<()> {
     let uuid_id = Uuid::parse_str(id)?;
     let query = r#"
         UPDATE blueprints
-        SET verifier_contract_address = $1
-        WHERE id = $2
+        SET verifier_contract_address = $1, verifier_contract_chain = $2
+        WHERE id = $3
     "#;
 
     sqlx::query(query)
         .bind(address)
+        .bind(chain_id as i32)
         .bind(uuid_id)
         .execute(pool)
         .await?;
@@ -23,6 +28,88 @@ pub async fn update_verifier_contract_address(
     Ok(())
 }
 
+/// Applies the migrations in `migrations/`. The database is shared with the registry, so
+/// migrations applied by other services are not treated as missing.
+pub async fn run_migrations(pool: &Pool<Postgres>) -> Result<()> {
+    let mut migrator = sqlx::migrate!("./migrations");
+    migrator.set_ignore_missing(true);
+    migrator.run(pool).await?;
+
+    Ok(())
+}
+
+/// Returns the address of `contract` recorded for the blueprint on `chain_id`, if any.
+pub async fn find_verifier_deployment(
+    pool: &Pool<Postgres>,
+    id: &str,
+    chain_id: u32,
+    contract: &str,
+) -> Result<Option<String>> {
+    let uuid_id = Uuid::parse_str(id)?;
+    let query = r#"
+        SELECT address FROM verifier_deployments
+        WHERE blueprint_id = $1 AND chain_id = $2 AND contract = $3
+    "#;
+
+    let address = sqlx::query_scalar(query)
+        .bind(uuid_id)
+        .bind(chain_id as i64)
+        .bind(contract)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(address)
+}
+
+/// Records the contracts of a deployment and their verifications, replacing earlier
+/// deployments to the same chain.
+pub async fn insert_verifier_deployment(
+    pool: &Pool<Postgres>,
+    id: &str,
+    deployment: &DeploymentResult,
+) -> Result<()> {
+    let uuid_id = Uuid::parse_str(id)?;
+    let query = r#"
+        INSERT INTO verifier_deployments (blueprint_id, chain_id, contract, address, tx_hash)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (blueprint_id, chain_id, contract)
+        DO UPDATE SET address = EXCLUDED.address, tx_hash = EXCLUDED.tx_hash, created_at = NOW()
+    "#;
+
+    for contract in &deployment.contracts {
+        sqlx::query(query)
+            .bind(uuid_id)
+            .bind(deployment.chain_id as i64)
+            .bind(&contract.name)
+            .bind(&contract.address)
+            .bind(&contract.tx_hash)
+            .execute(pool)
+            .await?;
+    }
+
+    let query = r#"
+        INSERT INTO verifier_contract_verifications
+            (blueprint_id, chain_id, contract, explorer, verified, error)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (blueprint_id, chain_id, contract, explorer)
+        DO UPDATE SET verified = EXCLUDED.verified, error = EXCLUDED.error, created_at = NOW()
+    "#;
+
+    for verification in &deployment.verifications {
+        sqlx::query(query)
+            .bind(uuid_id)
+            .bind(deployment.chain_id as i64)
+            .bind(&verification.contract)
+            .bind(verification.explorer)
+            .bind(verification.verified)
+            .bind(&verification.error)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
 // pub async fn update_ptau(pool: &Pool<Postgres>, id: Uuid, ptau: usize) -> Result<()> {
 //     let query = r#"
 //         UPDATE blueprints
@@ -0,0 +1,251 @@
+//! Deploys the compiled contracts over JSON-RPC, without Forge scripts or cast.
+
+use std::path::Path;
+
+use alloy::{
+    network::{EthereumWallet, ReceiptResponse, TransactionBuilder},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{anyhow, Result};
+use relayer_utils::LOG;
+use slog::info;
+
+use crate::{
+    create2::{creation_code, encode_addresses, format_address, parse_address},
+    deployment::{DeployedContract, DeploymentResult},
+};
+
+/// Sends deployment transactions signed with the deployer key to a single chain.
+pub struct NativeDeployer {
+    provider: DynProvider,
+    chain_id: u32,
+    /// Address of the deployer key, which becomes the owner of `Contract`.
+    owner: String,
+}
+
+fn parse_signer(private_key: &str) -> Result<PrivateKeySigner> {
+    private_key
+        .parse()
+        .map_err(|e| anyhow!("Invalid private key: {}", e))
+}
+
+/// Returns the address of `private_key`, the owner the deployed contracts are assigned to.
+pub fn signer_address(private_key: &str) -> Result<String> {
+    Ok(parse_signer(private_key)?.address().to_string())
+}
+
+impl NativeDeployer {
+    /// Connects to `rpc_url`, failing if the node serves another chain than `chain_id`.
+    pub async fn connect(rpc_url: &str, private_key: &str, chain_id: u32) -> Result<Self> {
+        let signer = parse_signer(private_key)?;
+        let owner = signer.address().to_string();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_http(rpc_url.parse()?)
+            .erased();
+
+        let node_chain_id = provider.get_chain_id().await?;
+        if node_chain_id != chain_id as u64 {
+            return Err(anyhow!(
+                "RPC URL serves chain {}, expected chain {}",
+                node_chain_id,
+                chain_id
+            ));
+        }
+        Ok(NativeDeployer {
+            provider,
+            chain_id,
+            owner,
+        })
+    }
+
+    /// Creates a contract from `init_code` and waits for its receipt.
+    pub async fn deploy(&self, name: &str, init_code: Vec<u8>) -> Result<DeployedContract> {
+        info!(LOG, "Deploying {} to chain {}", name, self.chain_id);
+        let tx = TransactionRequest::default().with_deploy_code(init_code);
+        let receipt = self
+            .provider
+            .send_transaction(tx)
+            .await?
+            .get_receipt()
+            .await?;
+        if !receipt.status() {
+            return Err(anyhow!(
+                "Deployment of {} reverted in {}",
+                name,
+                receipt.transaction_hash()
+            ));
+        }
+        let address = receipt
+            .contract_address()
+            .ok_or_else(|| anyhow!("No contract address in the receipt of {}", name))?;
+
+        Ok(DeployedContract {
+            name: name.to_string(),
+            address: address.to_string(),
+            tx_hash: Some(receipt.transaction_hash().to_string()),
+            gas_used: Some(receipt.gas_used()),
+            block_number: receipt.block_number(),
+        })
+    }
+
+    /// Deploys the verifiers and `Contract` from the Forge artifacts, using the existing
+    /// registry at `dkim_registry`.
+    pub async fn deploy_contracts(
+        &self,
+        artifacts_dir: &Path,
+        dkim_registry: &str,
+    ) -> Result<DeploymentResult> {
+        let client_proof_verifier = self
+            .deploy(
+                "ClientProofVerifier",
+                creation_code(artifacts_dir, "ClientProofVerifier")?,
+            )
+            .await?;
+        let server_proof_verifier = self
+            .deploy(
+                "ServerProofVerifier",
+                creation_code(artifacts_dir, "ServerProofVerifier")?,
+            )
+            .await?;
+
+        let mut init_code = creation_code(artifacts_dir, "Contract")?;
+        init_code.extend(encode_addresses(&[
+            parse_address(dkim_registry)?,
+            parse_address(&client_proof_verifier.address)?,
+            parse_address(&server_proof_verifier.address)?,
+            parse_address(&self.owner)?,
+        ]));
+        let contract = self.deploy("Contract", init_code).await?;
+
+        Ok(DeploymentResult {
+            chain_id: self.chain_id,
+            contracts: vec![
+                client_proof_verifier,
+                server_proof_verifier,
+                contract,
+                DeployedContract::existing(
+                    "DKIMRegistry",
+                    &format_address(&parse_address(dkim_registry)?),
+                ),
+            ],
+            verifications: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, net::TcpListener, process::Stdio};
+    use tokio::process::{Child, Command};
+
+    /// First account of the default anvil mnemonic.
+    const ANVIL_PRIVATE_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ANVIL_CHAIN_ID: u32 = 31337;
+
+    /// Init code returning a single `STOP` byte as runtime code, ignoring constructor args.
+    const STUB_INIT_CODE: &str = "0x6001600c60003960016000f300";
+
+    async fn has_code(deployer: &NativeDeployer, address: &str) -> bool {
+        let code = deployer
+            .provider
+            .get_code_at(address.parse().unwrap())
+            .await
+            .unwrap();
+        !code.is_empty()
+    }
+
+    /// Starts anvil on a free port, or returns `None` when Foundry is not installed.
+    async fn spawn_anvil() -> Option<(Child, String)> {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let anvil = match Command::new("anvil")
+            .args(["--port", &port.to_string()])
+            .stdout(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(anvil) => anvil,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => panic!("failed to start anvil: {}", e),
+        };
+
+        let rpc_url = format!("http://127.0.0.1:{}", port);
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse().unwrap());
+        for _ in 0..50 {
+            if provider.get_chain_id().await.is_ok() {
+                return Some((anvil, rpc_url));
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        panic!("anvil did not start");
+    }
+
+    #[test]
+    fn test_signer_address() {
+        assert_eq!(
+            signer_address(ANVIL_PRIVATE_KEY).unwrap(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert!(signer_address("0x1234").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_contracts_anvil() {
+        // Foundry is optional for native deployments, so the test is skipped without it
+        let Some((_anvil, rpc_url)) = spawn_anvil().await else {
+            println!("anvil not found, skipping");
+            return;
+        };
+
+        let artifacts_dir =
+            std::env::temp_dir().join(format!("deployer-artifacts-{}", std::process::id()));
+        for contract in ["ClientProofVerifier", "ServerProofVerifier", "Contract"] {
+            let dir = artifacts_dir.join(format!("{}.sol", contract));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(format!("{}.json", contract)),
+                format!(r#"{{"bytecode":{{"object":"{}"}}}}"#, STUB_INIT_CODE),
+            )
+            .unwrap();
+        }
+
+        assert!(NativeDeployer::connect(&rpc_url, ANVIL_PRIVATE_KEY, 84532)
+            .await
+            .is_err());
+        let deployer = NativeDeployer::connect(&rpc_url, ANVIL_PRIVATE_KEY, ANVIL_CHAIN_ID)
+            .await
+            .unwrap();
+        let registry = "0x3D3935B3C030893f118a84C92C66dF1B9E4169d6";
+        let deployment = deployer
+            .deploy_contracts(&artifacts_dir, registry)
+            .await
+            .unwrap();
+        fs::remove_dir_all(&artifacts_dir).unwrap();
+
+        assert_eq!(deployment.chain_id, ANVIL_CHAIN_ID);
+        for name in ["ClientProofVerifier", "ServerProofVerifier", "Contract"] {
+            let contract = deployment
+                .contracts
+                .iter()
+                .find(|contract| contract.name == name)
+                .unwrap();
+            assert!(has_code(&deployer, &contract.address).await);
+            assert!(contract.tx_hash.is_some());
+            assert!(contract.gas_used.unwrap() > 0);
+            assert!(contract.block_number.is_some());
+        }
+        assert!(!has_code(&deployer, registry).await);
+        assert_eq!(
+            deployment.address("DKIMRegistry").unwrap(),
+            registry.to_lowercase()
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Results of the deploy scripts, read from the broadcast files Forge writes.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::verification::VerificationResult;
+
+/// Directory Forge writes the transactions of broadcast scripts to.
+pub const BROADCAST_DIR: &str = "broadcast";
+
+/// A contract deployed by the deploy script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployedContract {
+    pub name: String,
+    pub address: String,
+    /// Transaction that created or upgraded the contract, `None` if it was deployed before.
+    pub tx_hash: Option<String>,
+    pub gas_used: Option<u64>,
+    pub block_number: Option<u64>,
+}
+
+impl DeployedContract {
+    /// A contract that already existed, so this deployment sent no transaction for it.
+    pub fn existing(name: &str, address: &str) -> Self {
+        DeployedContract {
+            name: name.to_string(),
+            address: address.to_string(),
+            tx_hash: None,
+            gas_used: None,
+            block_number: None,
+        }
+    }
+}
+
+/// Contracts deployed to a single chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeploymentResult {
+    pub chain_id: u32,
+    pub contracts: Vec<DeployedContract>,
+    /// Outcomes of verifying the contracts on block explorers.
+    pub verifications: Vec<VerificationResult>,
+}
+
+impl DeploymentResult {
+    pub fn address(&self, name: &str) -> Result<&str> {
+        self.contracts
+            .iter()
+            .find(|contract| contract.name == name)
+            .map(|contract| contract.address.as_str())
+            .ok_or_else(|| anyhow!("No {} deployed on chain {}", name, self.chain_id))
+    }
+
+    /// Returns the address proofs are verified at, the proxy for upgradeable deployments.
+    pub fn contract_address(&self) -> Result<&str> {
+        self.address("Contract")
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastFile {
+    transactions: Vec<BroadcastTransaction>,
+    #[serde(default)]
+    receipts: Vec<BroadcastReceipt>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastTransaction {
+    hash: Option<String>,
+    transaction_type: String,
+    contract_name: Option<String>,
+    contract_address: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastReceipt {
+    transaction_hash: String,
+    #[serde(default)]
+    status: Value,
+    #[serde(default)]
+    gas_used: Value,
+    #[serde(default)]
+    block_number: Value,
+}
+
+/// Transactions sent by a deploy script, joined with their receipts.
+#[derive(Debug)]
+pub struct Broadcast {
+    path: PathBuf,
+    transactions: Vec<BroadcastTransaction>,
+    receipts: Vec<BroadcastReceipt>,
+}
+
+/// Path of the broadcast file of the latest run of `script` on `chain_id`.
+pub fn broadcast_path(script: &str, chain_id: u32) -> PathBuf {
+    Path::new(BROADCAST_DIR)
+        .join(script)
+        .join(chain_id.to_string())
+        .join("run-latest.json")
+}
+
+/// Receipts encode quantities as hex strings, older Forge versions as numbers.
+fn quantity(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        },
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+impl Broadcast {
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read broadcast file {}: {}", path.display(), e))?;
+        let file: BroadcastFile = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Invalid broadcast file {}: {}", path.display(), e))?;
+
+        let broadcast = Broadcast {
+            path: path.to_path_buf(),
+            transactions: file.transactions,
+            receipts: file.receipts,
+        };
+        for receipt in &broadcast.receipts {
+            if quantity(&receipt.status) == Some(0) {
+                return Err(anyhow!(
+                    "Transaction {} in {} reverted",
+                    receipt.transaction_hash,
+                    path.display()
+                ));
+            }
+        }
+        Ok(broadcast)
+    }
+
+    fn contract(
+        &self,
+        name: &str,
+        transaction: &BroadcastTransaction,
+        address: &str,
+    ) -> DeployedContract {
+        let receipt = transaction.hash.as_ref().and_then(|hash| {
+            self.receipts
+                .iter()
+                .find(|receipt| receipt.transaction_hash.eq_ignore_ascii_case(hash))
+        });
+        DeployedContract {
+            name: name.to_string(),
+            address: address.to_string(),
+            tx_hash: transaction.hash.clone(),
+            gas_used: receipt.and_then(|receipt| quantity(&receipt.gas_used)),
+            block_number: receipt.and_then(|receipt| quantity(&receipt.block_number)),
+        }
+    }
+
+    /// Returns the contract `contract_name` created by the script under `name`, if any.
+    pub fn find_created(&self, contract_name: &str, name: &str) -> Option<DeployedContract> {
+        self.transactions
+            .iter()
+            .filter(|tx| tx.transaction_type == "CREATE" || tx.transaction_type == "CREATE2")
+            .find(|tx| tx.contract_name.as_deref() == Some(contract_name))
+            .and_then(|tx| {
+                let address = tx.contract_address.as_deref()?;
+                Some(self.contract(name, tx, address))
+            })
+    }
+
+    /// Returns the contract `contract_name` created by the script under `name`.
+    pub fn created(&self, contract_name: &str, name: &str) -> Result<DeployedContract> {
+        self.find_created(contract_name, name).ok_or_else(|| {
+            anyhow!(
+                "No {} created in broadcast file {}",
+                contract_name,
+                self.path.display()
+            )
+        })
+    }
+
+    /// Returns the existing contract at `address` under `name`, with the call the script
+    /// sent to it.
+    pub fn called(&self, address: &str, name: &str) -> Result<DeployedContract> {
+        self.transactions
+            .iter()
+            .filter(|tx| tx.transaction_type == "CALL")
+            .find(|tx| {
+                tx.contract_address
+                    .as_deref()
+                    .is_some_and(|to| to.eq_ignore_ascii_case(address))
+            })
+            .map(|tx| self.contract(name, tx, address))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No call to {} in broadcast file {}",
+                    address,
+                    self.path.display()
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROADCAST: &str = r#"{
+        "transactions": [
+            {
+                "hash": "0x0f2e0c3b9c4f0d7a3c7c9e52d8bce24ab0e9df8f2b1f6c2c0e7b6b0c1d2e3f40",
+                "transactionType": "CREATE",
+                "contractName": "ClientProofVerifier",
+                "contractAddress": "0x5fbdb2315678afecb367f032d93f642f64180aa3",
+                "function": null,
+                "arguments": null
+            },
+            {
+                "hash": "0x9a1b7c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b",
+                "transactionType": "CALL",
+                "contractName": "Contract",
+                "contractAddress": "0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0",
+                "function": "upgradeToAndCall(address,bytes)",
+                "arguments": []
+            }
+        ],
+        "receipts": [
+            {
+                "transactionHash": "0x0f2e0c3b9c4f0d7a3c7c9e52d8bce24ab0e9df8f2b1f6c2c0e7b6b0c1d2e3f40",
+                "status": "0x1",
+                "gasUsed": "0x1e8480",
+                "blockNumber": "0x1a4"
+            }
+        ],
+        "chain": 84532
+    }"#;
+
+    fn write_broadcast(content: &str, name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let path = write_broadcast(BROADCAST, "broadcast");
+        let broadcast = Broadcast::read(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let verifier = broadcast
+            .created("ClientProofVerifier", "ClientProofVerifier")
+            .unwrap();
+        assert_eq!(
+            verifier.address,
+            "0x5fbdb2315678afecb367f032d93f642f64180aa3"
+        );
+        assert_eq!(verifier.gas_used, Some(2_000_000));
+        assert_eq!(verifier.block_number, Some(420));
+        assert!(broadcast
+            .created("ServerProofVerifier", "ServerProofVerifier")
+            .is_err());
+
+        let proxy = broadcast
+            .called("0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0", "Contract")
+            .unwrap();
+        assert_eq!(
+            proxy.tx_hash.as_deref(),
+            Some("0x9a1b7c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b")
+        );
+        assert_eq!(proxy.gas_used, None);
+
+        let result = DeploymentResult {
+            chain_id: 84532,
+            contracts: vec![verifier],
+            verifications: Vec::new(),
+        };
+        assert!(result.address("ClientProofVerifier").is_ok());
+        assert!(result.contract_address().is_err());
+    }
+
+    #[test]
+    fn test_reverted_broadcast() {
+        let path = write_broadcast(
+            &BROADCAST.replace(r#""status": "0x1""#, r#""status": "0x0""#),
+            "reverted-broadcast",
+        );
+        let result = Broadcast::read(&path);
+        fs::remove_file(path).unwrap();
+        assert!(result.is_err());
+    }
+}
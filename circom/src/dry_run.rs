@@ -0,0 +1,143 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use relayer_utils::LOG;
+use sdk_utils::{proto_types::proto_blueprint::Blueprint, validate_blueprint};
+use slog::info;
+
+use crate::{
+    contract::{create_contract, prepare_contract_data},
+    payload::load_payload,
+    template::{
+        generate_circuit, generate_regex_circuits, write_input_options_json, write_signals_json,
+        CircuitTemplateInputs,
+    },
+};
+
+/// Options for rendering a blueprint without compiling it,
+/// passed as `--dry-run <out_dir> [--blueprint <file.json>]`.
+pub struct DryRunOptions {
+    pub out_dir: PathBuf,
+    pub blueprint_path: Option<PathBuf>,
+}
+
+impl DryRunOptions {
+    /// Parses the dry-run flags from the command line, returning `None` for a regular build.
+    pub fn from_args(args: &[String]) -> Result<Option<Self>> {
+        let Some(position) = args.iter().position(|arg| arg == "--dry-run") else {
+            return Ok(None);
+        };
+
+        let out_dir = args
+            .get(position + 1)
+            .filter(|arg| !arg.starts_with("--"))
+            .ok_or_else(|| anyhow!("--dry-run requires an output directory"))?;
+
+        let blueprint_path = match args.iter().position(|arg| arg == "--blueprint") {
+            Some(position) => {
+                Some(PathBuf::from(args.get(position + 1).ok_or_else(|| {
+                    anyhow!("--blueprint requires a path to a blueprint JSON")
+                })?))
+            }
+            None => None,
+        };
+
+        Ok(Some(DryRunOptions {
+            out_dir: PathBuf::from(out_dir),
+            blueprint_path,
+        }))
+    }
+
+    /// Loads the blueprint from `--blueprint` if given, otherwise from the `PAYLOAD` env var.
+    pub fn load_blueprint(&self) -> Result<Blueprint> {
+        match &self.blueprint_path {
+            Some(path) => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            None => Ok(load_payload()?.blueprint),
+        }
+    }
+}
+
+/// Validates the blueprint and renders the regex circuits, main circuit and contract
+/// into `out_dir`, then prints the resulting signal layout.
+pub fn dry_run(blueprint: Blueprint, out_dir: &Path) -> Result<()> {
+    info!(LOG, "Dry run"; "out_dir" => out_dir.display().to_string());
+
+    validate_blueprint(&blueprint)?;
+
+    let regex_dir = out_dir.join("regex");
+    fs::create_dir_all(&regex_dir)?;
+    generate_regex_circuits(blueprint.decomposed_regexes.clone(), &regex_dir)?;
+
+    let circuit_template_inputs = CircuitTemplateInputs::from(blueprint.clone());
+    let layout = circuit_template_inputs.layout().clone();
+    write_input_options_json(circuit_template_inputs.input_generation_options(), out_dir)?;
+    let circuit = generate_circuit(circuit_template_inputs)?;
+    fs::write(out_dir.join("circuit.circom"), circuit)?;
+    write_signals_json(&layout, out_dir)?;
+
+    let contract_data = prepare_contract_data(&blueprint);
+    create_contract(&contract_data, out_dir)?;
+
+    println!(
+        "Rendered {} into {}",
+        blueprint.circuit_name,
+        out_dir.display()
+    );
+
+    println!("\nRegexes:");
+    for regex in &blueprint.decomposed_regexes {
+        let public_parts = regex
+            .parts
+            .iter()
+            .filter(|part| part.is_public == Some(true))
+            .count();
+        println!(
+            "  {} ({}, max match length {}, {} public part(s){})",
+            regex.name,
+            regex.location,
+            regex.max_match_length,
+            public_parts,
+            if regex.is_hashed.unwrap_or(false) {
+                ", hashed"
+            } else {
+                ""
+            }
+        );
+    }
+
+    println!("\nCircuit public signals:");
+    for signal in &layout.signals {
+        println!(
+            "  [{}..{}] {}",
+            signal.start_idx,
+            signal.start_idx + signal.width,
+            signal.name
+        );
+    }
+    println!("  total: {}", layout.len());
+
+    println!("\nContract signal layout:");
+    for field in contract_data
+        .values
+        .iter()
+        .chain(contract_data.external_inputs.iter())
+    {
+        println!(
+            "  [{}..{}] {} (max length {})",
+            field.start_idx,
+            field.start_idx + field.pack_size,
+            field.name,
+            field.max_length
+        );
+    }
+    println!(
+        "  [{}] proverETHAddress",
+        contract_data.prover_eth_address_idx
+    );
+    println!("  signal size: {}", contract_data.signal_size);
+
+    Ok(())
+}
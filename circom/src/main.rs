@@ -1,28 +1,52 @@
 mod contract;
+mod create2;
 mod db;
+mod deployer;
+mod deployment;
+mod dry_run;
 mod payload;
+mod reproducible;
 mod template;
+mod verification;
+mod zkey;
 
 use std::{cmp::max, fs, path::Path};
 
 use anyhow::Result;
 use contract::{
-    create_contract, deploy_verifier_contract, generate_verifier_contract, prepare_contract_data,
+    build_contracts, create_contract, deploy_verifier_contract, generate_verifier_contract,
+    prepare_contract_data,
 };
-use db::update_verifier_contract_address;
+use db::{
+    find_verifier_deployment, insert_verifier_deployment, run_migrations,
+    update_verifier_contract_address,
+};
+use dry_run::{dry_run, DryRunOptions};
 use payload::UploadUrls;
 use rand::Rng;
 use relayer_utils::LOG;
+use reproducible::{blueprint_beacon, write_attestation, DEFAULT_BEACON};
 use sdk_utils::{
-    proto_types::proto_blueprint::Blueprint, run_command, run_command_and_return_output,
-    run_command_with_input, upload_to_url,
+    gzip_file, move_file, proto_types::proto_blueprint::Blueprint, run_command,
+    run_command_and_return_output, run_command_with_input, upload_to_url, validate_blueprint,
+    zip_dir_filtered, zip_paths,
 };
 use slog::info;
 use sqlx::postgres::PgPoolOptions;
-use template::{generate_circuit, generate_regex_circuits, CircuitTemplateInputs};
+use template::{
+    generate_circuit, generate_regex_circuits, verify_sym_layout, write_input_options_json,
+    write_signals_json, CircuitTemplateInputs,
+};
+use verification::check_verifications;
+use zkey::{check_chunk_destinations, discover_zkey_chunks, write_manifest};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(options) = DryRunOptions::from_args(&args)? {
+        return dry_run(options.load_blueprint()?, &options.out_dir);
+    }
+
     let payload = payload::load_payload()?;
     info!(LOG, "Loaded configuration: {:?}", payload);
     println!("payload: {:?}", payload);
@@ -31,6 +55,7 @@ async fn main() -> Result<()> {
         .max_connections(10)
         .connect(&payload.database_url)
         .await?;
+    run_migrations(&pool).await?;
 
     let blueprint = payload.clone().blueprint;
 
@@ -38,11 +63,23 @@ async fn main() -> Result<()> {
 
     println!("ptau: {}", ptau);
 
-    generate_keys("tmp", ptau).await?;
+    // In reproducible mode the zkey beacon is derived from the blueprint instead of being fixed
+    let reproducible_beacon = payload.reproducible.then(|| blueprint_beacon(&blueprint));
+    if let Some(beacon) = &reproducible_beacon {
+        info!(LOG, "Reproducible mode enabled"; "beacon" => beacon);
+    }
+
+    generate_keys("tmp", ptau, reproducible_beacon.as_deref()).await?;
 
-    let contract_data = prepare_contract_data(&payload);
+    let zkey_chunks = discover_zkey_chunks("tmp", "circuit.zkey")?;
+    info!(LOG, "Found {} zkey chunks", zkey_chunks.len());
+    check_chunk_destinations(&payload.upload_urls, &zkey_chunks)?;
+    write_manifest("tmp", &zkey_chunks)?;
 
-    create_contract(&contract_data)?;
+    let mut contract_data = prepare_contract_data(&blueprint);
+    contract_data.upgradeable = payload.upgradeable;
+
+    create_contract(&contract_data, Path::new("tmp"))?;
 
     // We use two different snarkjs paths:
     // 1. snarkjs_path: The global snarkjs installation for server-side proofs (full zkey)
@@ -71,15 +108,61 @@ async fn main() -> Result<()> {
     )
     .await?;
 
-    let contract_address = deploy_verifier_contract(payload.clone()).await?;
+    build_contracts().await?;
+
+    // Deploy to every chain before failing, so one unreachable chain does not lose the others
+    let mut deployments = Vec::new();
+    let mut failed_chains = Vec::new();
+    for target in payload.chain_targets()? {
+        let recorded_contract =
+            find_verifier_deployment(&pool, &blueprint.id, target.chain_id, "Contract").await?;
+        match deploy_verifier_contract(&payload, &target, recorded_contract.as_deref()).await {
+            Ok(deployment) => {
+                info!(
+                    LOG,
+                    "Contract deployed on chain {} at: {}",
+                    target.chain_id,
+                    deployment.contract_address()?
+                );
+                deployments.push(deployment);
+            }
+            Err(e) => {
+                info!(LOG, "Deployment to chain {} failed: {}", target.chain_id, e);
+                failed_chains.push(target.chain_id);
+            }
+        }
+    }
+
+    cleanup(&zkey_chunks).await?;
 
-    info!(LOG, "Contract deployed at: {}", contract_address);
+    if let Some(beacon) = &reproducible_beacon {
+        info!(LOG, "Writing build attestation");
+        write_attestation("tmp", &blueprint, beacon).await?;
+    }
 
-    cleanup().await?;
+    for deployment in &deployments {
+        insert_verifier_deployment(&pool, &blueprint.id, deployment).await?;
+    }
+    // The blueprint keeps the address on the first chain deployed to
+    if let Some(deployment) = deployments.first() {
+        update_verifier_contract_address(
+            &pool,
+            &blueprint.id,
+            deployment.chain_id,
+            deployment.contract_address()?,
+        )
+        .await?;
+    }
 
-    update_verifier_contract_address(&pool, &blueprint.id, &contract_address).await?;
+    upload_files(payload.upload_urls, &zkey_chunks).await?;
 
-    upload_files(payload.upload_urls).await?;
+    if !failed_chains.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Deployment failed on chains {:?}",
+            failed_chains
+        ));
+    }
+    check_verifications(&payload.verification, &deployments)?;
 
     Ok(())
 }
@@ -111,7 +194,7 @@ async fn setup() -> Result<()> {
     }
     fs::create_dir_all(&regex_path)?;
 
-    run_command("cp", &["package.json", "./tmp"], None).await?;
+    fs::copy("package.json", tmp_path.join("package.json"))?;
     Ok(())
 }
 
@@ -191,15 +274,10 @@ async fn compile_circuit() -> Result<usize> {
 
         // Move the binary
         info!(LOG, "Copying binary");
-        run_command(
-            "mv",
-            &[
-                "witness_generator",
-                &format!("{}/tmp/circuit_cpp/circuit", current_dir_str),
-            ],
-            Some(format!("{}/vendors/circom", tachyon_dir_str).as_str()),
-        )
-        .await?;
+        move_file(
+            format!("{}/vendors/circom/witness_generator", tachyon_dir_str),
+            format!("{}/tmp/circuit_cpp/circuit", current_dir_str),
+        )?;
     } else {
         info!(LOG, "Skipping binary compilation - TACHYON_DIR not set");
     }
@@ -207,7 +285,13 @@ async fn compile_circuit() -> Result<usize> {
     Ok(k as usize)
 }
 
-async fn generate_keys(tmp_dir: &str, ptau: usize) -> Result<()> {
+async fn generate_keys(
+    tmp_dir: &str,
+    ptau: usize,
+    reproducible_beacon: Option<&str>,
+) -> Result<()> {
+    let beacon = reproducible_beacon.unwrap_or(DEFAULT_BEACON);
+
     // Generate the proving and verification keys
     info!(LOG, "Downloading ptau file");
     run_command(
@@ -261,8 +345,10 @@ async fn generate_keys(tmp_dir: &str, ptau: usize) -> Result<()> {
 
     // Contribute to zkey
     info!(LOG, "Contributing to zkey");
-    let random_input: u32 = rand::thread_rng().gen_range(100..1000);
-    let random_input_str = format!("{}\n", random_input);
+    let random_input_str = match reproducible_beacon {
+        Some(beacon) => format!("{}\n", beacon),
+        None => format!("{}\n", rand::thread_rng().gen_range(100..1000)),
+    };
     run_command_with_input(
         "snarkjs",
         &[
@@ -270,7 +356,7 @@ async fn generate_keys(tmp_dir: &str, ptau: usize) -> Result<()> {
             "beacon",
             "circuit_0000.zkey",
             "circuit_full.zkey",
-            "0102030405060708090a0b0c0d0e0f101112231415161718221a1b1c1d1e1f",
+            beacon,
             "10",
         ],
         Some(tmp_dir),
@@ -308,21 +394,18 @@ async fn generate_keys(tmp_dir: &str, ptau: usize) -> Result<()> {
             "beacon",
             "circuit_0000.zkey",
             "circuit.zkey",
-            "0102030405060708090a0b0c0d0e0f101112231415161718221a1b1c1d1e1f",
+            beacon,
             "10",
         ],
         Some(tmp_dir),
     )
     .await?;
 
-    run_command("rm", &["pot_final.ptau"], Some(tmp_dir)).await?;
-    run_command("rm", &["circuit_0000.zkey"], Some(tmp_dir)).await?;
-    for c in ['b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k'] {
-        let filename = format!("circuit_0000.zkey{}", c);
-        let file_path = Path::new("tmp").join(&filename);
-        if file_path.exists() {
-            run_command("rm", &[&filename], Some("tmp")).await?;
-        }
+    let tmp_path = Path::new(tmp_dir);
+    fs::remove_file(tmp_path.join("pot_final.ptau"))?;
+    fs::remove_file(tmp_path.join("circuit_0000.zkey"))?;
+    for c in discover_zkey_chunks(tmp_dir, "circuit_0000.zkey")? {
+        fs::remove_file(tmp_path.join(format!("circuit_0000.zkey{}", c)))?;
     }
 
     // Export verification key
@@ -343,32 +426,32 @@ async fn generate_keys(tmp_dir: &str, ptau: usize) -> Result<()> {
     Ok(())
 }
 
-async fn cleanup() -> Result<()> {
+async fn cleanup(zkey_chunks: &[String]) -> Result<()> {
     info!(LOG, "Cleaning up");
 
-    run_command("cp", &["remappings.txt", "./tmp"], None).await?;
-    run_command("cp", &["package.json", "./tmp"], None).await?;
-    run_command("cp", &["foundry.toml", "./tmp"], None).await?;
-    run_command("cp", &["Deploy.s.sol", "./tmp"], None).await?;
+    let tmp_path = Path::new("tmp");
+    for file in [
+        "remappings.txt",
+        "package.json",
+        "foundry.toml",
+        "Deploy.s.sol",
+    ] {
+        fs::copy(file, tmp_path.join(file))?;
+    }
 
     // After generating the chunked zkey, add compression steps
     info!(LOG, "Compressing zkey chunks");
-    for c in ['b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k'] {
-        let filename = format!("circuit.zkey{}", c);
-        if Path::new(&format!("{}/{}", "tmp", filename)).exists() {
-            run_command("gzip", &[&filename], Some("tmp")).await?;
-        }
+    for c in zkey_chunks {
+        gzip_file(tmp_path.join(format!("circuit.zkey{}", c)))?;
     }
 
     info!(LOG, "Zipping full zkey");
-    run_command("gzip", &["circuit.zkey"], Some("tmp")).await?;
+    gzip_file(tmp_path.join("circuit.zkey"))?;
 
     info!(LOG, "Zipping files");
-    run_command(
-        "zip",
+    zip_paths(
+        tmp_path,
         &[
-            "-r",
-            "circuit.zip",
             "regex",
             "circuit.circom",
             "Contract.sol",
@@ -379,41 +462,45 @@ async fn cleanup() -> Result<()> {
             "ClientProofVerifier.sol",
             "ServerProofVerifier.sol",
         ],
-        Some("tmp"),
-    )
-    .await?;
-
-    run_command(
-        "zip",
-        &["-r", "circuit_cpp.zip", "."],
-        Some("tmp/circuit_cpp"),
-    )
-    .await?;
-
-    run_command("mv", &["circuit_full.zkey", "circuit.zkey"], Some("tmp")).await?;
-
-    run_command(
-        "zip",
-        &["-r", "circuit_full_zkey.zip", "circuit.zkey"],
-        Some("tmp"),
-    )
-    .await?;
-
-    run_command("mv", &["verification_key.json", "vk.json"], Some("tmp")).await?;
+        tmp_path.join("circuit.zip"),
+    )?;
+
+    let circuit_cpp_path = tmp_path.join("circuit_cpp");
+    zip_dir_filtered(
+        &circuit_cpp_path,
+        circuit_cpp_path.join("circuit_cpp.zip"),
+        |_| true,
+    )?;
+
+    move_file(
+        tmp_path.join("circuit_full.zkey"),
+        tmp_path.join("circuit.zkey"),
+    )?;
+
+    zip_paths(
+        tmp_path,
+        &["circuit.zkey"],
+        tmp_path.join("circuit_full_zkey.zip"),
+    )?;
+
+    move_file(
+        tmp_path.join("verification_key.json"),
+        tmp_path.join("vk.json"),
+    )?;
 
     // Create regex circuit zip file
     info!(LOG, "Creating regex graph zip file");
-    run_command(
-        "zip",
-        &["-r", "circomRegexGraphs.zip", ".", "-i", "*_regex.json"],
-        Some("tmp/regex/"),
-    )
-    .await?;
+    let regex_path = tmp_path.join("regex");
+    zip_dir_filtered(
+        &regex_path,
+        regex_path.join("circomRegexGraphs.zip"),
+        |path| path.to_string_lossy().ends_with("_regex.json"),
+    )?;
 
     Ok(())
 }
 
-async fn upload_files(upload_urls: UploadUrls) -> Result<()> {
+async fn upload_files(upload_urls: UploadUrls, zkey_chunks: &[String]) -> Result<()> {
     info!(LOG, "Uploading files");
 
     // Define required uploads with their paths and content types
@@ -461,27 +548,44 @@ async fn upload_files(upload_urls: UploadUrls) -> Result<()> {
         }
     }
 
-    // Handle chunked zkey files (b through k)
-    let chunked_urls = [
-        (&upload_urls.zkey_b, 'b'),
-        (&upload_urls.zkey_c, 'c'),
-        (&upload_urls.zkey_d, 'd'),
-        (&upload_urls.zkey_e, 'e'),
-        (&upload_urls.zkey_f, 'f'),
-        (&upload_urls.zkey_g, 'g'),
-        (&upload_urls.zkey_h, 'h'),
-        (&upload_urls.zkey_i, 'i'),
-        (&upload_urls.zkey_j, 'j'),
-        (&upload_urls.zkey_k, 'k'),
-    ];
-
-    for (url, chunk) in chunked_urls {
+    // Every chunk has a URL, `check_chunk_destinations` ran right after key generation
+    for chunk in zkey_chunks {
         let path = format!("./tmp/circuit.zkey{}.gz", chunk);
-        if Path::new(&path).exists() {
-            upload_to_url(url, &path, "application/octet-stream").await?;
-        } else {
-            info!(LOG, "Skipping upload for missing chunk: {}", path);
+        upload_to_url(
+            &upload_urls.zkey_chunks[chunk],
+            &path,
+            "application/octet-stream",
+        )
+        .await?;
+    }
+
+    let manifest_path = "./tmp/manifest.json";
+    if let Some(url) = &upload_urls.manifest {
+        upload_to_url(url, manifest_path, "application/json").await?;
+    } else {
+        info!(LOG, "Skipping upload for manifest: no URL provided");
+    }
+
+    let attestation_path = "./tmp/attestation.json";
+    match &upload_urls.attestation {
+        Some(url) if Path::new(attestation_path).exists() => {
+            upload_to_url(url, attestation_path, "application/json").await?;
         }
+        _ => info!(LOG, "Skipping upload for attestation"),
+    }
+
+    let signals_path = "./tmp/signals.json";
+    if let Some(url) = &upload_urls.signals {
+        upload_to_url(url, signals_path, "application/json").await?;
+    } else {
+        info!(LOG, "Skipping upload for signals: no URL provided");
+    }
+
+    let input_options_path = "./tmp/input_options.json";
+    if let Some(url) = &upload_urls.input_options {
+        upload_to_url(url, input_options_path, "application/json").await?;
+    } else {
+        info!(LOG, "Skipping upload for input options: no URL provided");
     }
 
     let regex_json_path = "./tmp/regex/circomRegexGraphs.zip";
@@ -503,11 +607,21 @@ async fn upload_files(upload_urls: UploadUrls) -> Result<()> {
 }
 
 pub async fn process_circuit(blueprint: Blueprint) -> Result<usize> {
+    validate_blueprint(&blueprint)?;
+
     setup().await?;
 
-    generate_regex_circuits(blueprint.clone().decomposed_regexes)?;
+    generate_regex_circuits(
+        blueprint.clone().decomposed_regexes,
+        Path::new("./tmp/regex"),
+    )?;
 
     let circuit_template_inputs = CircuitTemplateInputs::from(blueprint.clone());
+    let layout = circuit_template_inputs.layout().clone();
+    write_input_options_json(
+        circuit_template_inputs.input_generation_options(),
+        Path::new("./tmp"),
+    )?;
 
     let circuit = generate_circuit(circuit_template_inputs)?;
 
@@ -521,6 +635,10 @@ pub async fn process_circuit(blueprint: Blueprint) -> Result<usize> {
 
     let ptau: usize = compile_circuit().await?;
 
+    // Fail early if the compiled circuit disagrees with the layout the contract is built from
+    verify_sym_layout(&layout, &fs::read_to_string("./tmp/circuit.sym")?)?;
+    write_signals_json(&layout, Path::new("./tmp"))?;
+
     Ok(ptau)
 }
 
@@ -528,72 +646,11 @@ pub async fn process_circuit(blueprint: Blueprint) -> Result<usize> {
 mod tests {
     use super::*;
 
-    use prost_wkt_types::Timestamp;
-    use sdk_utils::proto_types::proto_blueprint::{
-        Blueprint, DecomposedRegex, DecomposedRegexPart, ExternalInput,
-    };
+    use sdk_utils::{fixtures, proto_types::proto_blueprint::ZkFramework, SUPPORTED_RSA_KEY_BITS};
 
     #[tokio::test]
     async fn test_compile_circuit_x_export_data() {
-        let blueprint = Blueprint {
-            internal_version: "v2".to_string(),
-            id: "4478f3bc-9ba8-4906-ba87-09fc049cef46".to_string(),
-            title: "XAccountExportData".to_string(),
-            description:
-                "Prove you've asked to export your twitter/X data and reveal only the download link"
-                    .to_string(),
-            slug: "DimiDumo/XAccountExportData".to_string(),
-            tags: vec![],
-            email_query: "from:x.com".to_string(),
-            circuit_name: "XAccountExportData".to_string(),
-            ignore_body_hash_check: false,
-            sha_precompute_selector: "".to_string(),
-            email_body_max_length: 6208,
-            sender_domain: "x.com".to_string(),
-            enable_header_masking: false,
-            enable_body_masking: false,
-            client_zk_framework: 1, // Circom
-            server_zk_framework: 0, // None
-            verifier_contract_chain: 84532,
-            verifier_contract_address: "0x6679b65c5CFCba507Bf105491A3b5B68764B1464".to_string(),
-            is_public: true,
-            created_at: Some(Timestamp {
-                seconds: 1746574183,
-                nanos: 310124000,
-            }),
-            updated_at: Some(Timestamp {
-                seconds: 1746574183,
-                nanos: 310124000,
-            }),
-            external_inputs: vec![],
-            decomposed_regexes: vec![DecomposedRegex {
-                name: "downloadDataLink".to_string(),
-                max_match_length: 128,
-                location: "body".to_string(),
-                is_hashed: Some(false),
-                parts: vec![
-                    DecomposedRegexPart {
-                        is_public: Some(false),
-                        regex_def: "ready for you to download ".to_string(),
-                        max_length: None,
-                    },
-                    DecomposedRegexPart {
-                        is_public: Some(true),
-                        regex_def: "[^ ]*".to_string(),
-                        max_length: Some(20),
-                    },
-                ],
-            }],
-            client_status: 1, // InProgress
-            server_status: 3, // Done
-            version: 1,
-            github_username: "DimiDumo".to_string(),
-            email_header_max_length: 1024,
-            remove_soft_linebreaks: true,
-            stars: 0,
-            ptau: 0,
-            num_local_proofs: 0,
-        };
+        let blueprint = fixtures::x_export_data(ZkFramework::Circom);
 
         // Call the handler with the mock uploader
         let result = process_circuit(blueprint).await;
@@ -608,73 +665,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_compile_circuit_apple() {
-        let blueprint = Blueprint {
-            internal_version: "v2".to_string(),
-            id: "88802381-0501-4c4a-bcb5-03fdeacf453e".to_string(),
-            title: "AppleKYC".to_string(),
-            description: "Prove you have a valid Apple account".to_string(),
-            slug: "DimiDumo/AppleKYC".to_string(),
-            tags: vec![],
-            email_query: "from:email.apple.com".to_string(),
-            circuit_name: "AppleKYC".to_string(),
-            ignore_body_hash_check: true,
-            sha_precompute_selector: "".to_string(),
-            email_body_max_length: 0,
-            sender_domain: "email.apple.com".to_string(),
-            enable_header_masking: false,
-            enable_body_masking: false,
-            client_zk_framework: 1, // Circom
-            server_zk_framework: 0, // None
-            verifier_contract_chain: 84532,
-            verifier_contract_address: "0x1E8AbE8B8551E73d25239004EffccA2d077eF146".to_string(),
-            is_public: true,
-            created_at: Some(Timestamp {
-                seconds: 1746538605,
-                nanos: 86528000,
-            }),
-            updated_at: Some(Timestamp {
-                seconds: 1746538605,
-                nanos: 86528000,
-            }),
-            external_inputs: vec![ExternalInput {
-                name: "address".to_string(),
-                max_length: 44,
-            }],
-            decomposed_regexes: vec![
-                DecomposedRegex {
-                    name: "Subject".to_string(),
-                    max_match_length: 256,
-                    location: "header".to_string(),
-                    is_hashed: Some(false),
-                    parts: vec![
-                        DecomposedRegexPart {
-                            is_public: Some(false),
-                            regex_def: "(?:\r\n|^)subject:".to_string(),
-                            max_length: None,
-                        },
-                        DecomposedRegexPart {
-                            is_public: Some(true),
-                            regex_def: "[^\r\n]+".to_string(),
-                            max_length: Some(20),
-                        },
-                        DecomposedRegexPart {
-                            is_public: Some(false),
-                            regex_def: "\r\n".to_string(),
-                            max_length: None,
-                        },
-                    ],
-                }, // Other DecomposedRegex objects omitted for brevity - add them if needed
-            ],
-            client_status: 1, // InProgress
-            server_status: 3, // Done
-            version: 6,
-            github_username: "DimiDumo".to_string(),
-            email_header_max_length: 2048,
-            remove_soft_linebreaks: true,
-            stars: 0,
-            ptau: 0,
-            num_local_proofs: 0,
-        };
+        let blueprint = fixtures::apple_kyc(ZkFramework::Circom);
 
         let result = process_circuit(blueprint).await;
 
@@ -688,68 +679,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_compile_circuit_registry() {
-        let blueprint = Blueprint {
-            internal_version: "v2".to_string(),
-            id: "87ec6e2f-ca5a-4af8-ac85-2e2cc94602f0".to_string(),
-            title: "Sp1Residency".to_string(),
-            description: "Sp1Residency".to_string(),
-            slug: "DimiDumo/sp1_residency".to_string(),
-            tags: vec![],
-            email_query: "".to_string(),
-            circuit_name: "sp1_residency".to_string(),
-            ignore_body_hash_check: true,
-            sha_precompute_selector: "".to_string(),
-            email_body_max_length: 0,
-            sender_domain: "succinct.xyz".to_string(),
-            enable_header_masking: false,
-            enable_body_masking: false,
-            client_zk_framework: 1, // Circom
-            server_zk_framework: 0, // None
-            verifier_contract_chain: 0,
-            verifier_contract_address: "".to_string(),
-            is_public: true,
-            created_at: Some(Timestamp {
-                seconds: 1746543161,
-                nanos: 36149000,
-            }),
-            updated_at: Some(Timestamp {
-                seconds: 1746543161,
-                nanos: 36149000,
-            }),
-            external_inputs: vec![],
-            decomposed_regexes: vec![DecomposedRegex {
-                name: "Subject".to_string(),
-                max_match_length: 50,
-                location: "header".to_string(),
-                is_hashed: Some(false),
-                parts: vec![
-                    DecomposedRegexPart {
-                        is_public: Some(false),
-                        regex_def: "Welcome ".to_string(),
-                        max_length: None,
-                    },
-                    DecomposedRegexPart {
-                        is_public: Some(true),
-                        regex_def: "to the ".to_string(),
-                        max_length: Some(20),
-                    },
-                    DecomposedRegexPart {
-                        is_public: Some(false),
-                        regex_def: "Succinct ZK Residency!".to_string(),
-                        max_length: None,
-                    },
-                ],
-            }],
-            client_status: 1, // InProgress
-            server_status: 3, // Done
-            version: 31,
-            github_username: "DimiDumo".to_string(),
-            email_header_max_length: 896,
-            remove_soft_linebreaks: false,
-            stars: 0,
-            ptau: 0,
-            num_local_proofs: 0,
-        };
+        let blueprint = fixtures::succinct_residency(ZkFramework::Circom);
 
         let result = process_circuit(blueprint).await;
 
@@ -763,66 +693,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_compile_circuit_kraken() {
-        let blueprint = Blueprint {
-            internal_version: "v2".to_string(),
-            id: "85255ee2-acfe-49ca-959c-edd009b53bb5".to_string(),
-            title: "Kraken KYC (Intermediate)".to_string(),
-            description: "Proof of Kraken Intermediate Account".to_string(),
-            slug: "Bisht13/krakenintermediate".to_string(),
-            tags: vec![],
-            email_query: "from:kraken.com".to_string(),
-            circuit_name: "krakenintermediate".to_string(),
-            ignore_body_hash_check: true,
-            sha_precompute_selector: "".to_string(),
-            email_body_max_length: 4096,
-            sender_domain: "kraken.com".to_string(),
-            enable_header_masking: false,
-            enable_body_masking: false,
-            client_zk_framework: 1, // Circom
-            server_zk_framework: 0, // None
-            verifier_contract_chain: 84532,
-            verifier_contract_address: "".to_string(),
-            is_public: true,
-            created_at: Some(Timestamp {
-                seconds: 1736325873,
-                nanos: 967251000,
-            }),
-            updated_at: Some(Timestamp {
-                seconds: 1736326473,
-                nanos: 627382000,
-            }),
-            external_inputs: vec![ExternalInput {
-                name: "test".to_string(),
-                max_length: 4096,
-            }],
-            decomposed_regexes: vec![DecomposedRegex {
-                name: "EmailSubject".to_string(),
-                max_match_length: 64,
-                location: "header".to_string(),
-                is_hashed: Some(true),
-                parts: vec![
-                    DecomposedRegexPart {
-                        is_public: Some(true),
-                        regex_def: "subject:".to_string(),
-                        max_length: Some(20),
-                    },
-                    DecomposedRegexPart {
-                        is_public: Some(true),
-                        regex_def: "Good news: your account is now Intermediate!".to_string(),
-                        max_length: Some(20),
-                    },
-                ],
-            }],
-            client_status: 1, // InProgress
-            server_status: 3, // Done
-            version: 1,
-            github_username: "Bisht13".to_string(),
-            email_header_max_length: 1088,
-            remove_soft_linebreaks: false,
-            stars: 0,
-            ptau: 0,
-            num_local_proofs: 0,
-        };
+        let blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
 
         // Call the handler with the mock uploader
         let result = process_circuit(blueprint).await;
@@ -837,68 +708,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_compile_circuit_subject_extract() {
-        let blueprint = Blueprint {
-            internal_version: "v2".to_string(),
-            id: "87ec6e2f-ca5a-4af8-ac85-2e2cc94602f0".to_string(),
-            title: "Sp1Residency".to_string(),
-            description: "Sp1Residency".to_string(),
-            slug: "DimiDumo/sp1_residency".to_string(),
-            tags: vec![],
-            email_query: "".to_string(),
-            circuit_name: "sp1_residency".to_string(),
-            ignore_body_hash_check: true,
-            sha_precompute_selector: "".to_string(),
-            email_body_max_length: 0,
-            sender_domain: "succinct.xyz".to_string(),
-            enable_header_masking: false,
-            enable_body_masking: false,
-            client_zk_framework: 1, // Circom
-            server_zk_framework: 0, // None
-            verifier_contract_chain: 0,
-            verifier_contract_address: "".to_string(),
-            is_public: true,
-            created_at: Some(Timestamp {
-                seconds: 1746543161,
-                nanos: 36149000,
-            }),
-            updated_at: Some(Timestamp {
-                seconds: 1746543161,
-                nanos: 36149000,
-            }),
-            external_inputs: vec![],
-            decomposed_regexes: vec![DecomposedRegex {
-                name: "Subject".to_string(),
-                max_match_length: 64,
-                location: "header".to_string(),
-                is_hashed: Some(false),
-                parts: vec![
-                    DecomposedRegexPart {
-                        is_public: Some(false),
-                        regex_def: "(?:\r\n|^)subject:".to_string(),
-                        max_length: None,
-                    },
-                    DecomposedRegexPart {
-                        is_public: Some(true),
-                        regex_def: "[a-z]+".to_string(),
-                        max_length: Some(20),
-                    },
-                    DecomposedRegexPart {
-                        is_public: Some(false),
-                        regex_def: "\r\n".to_string(),
-                        max_length: None,
-                    },
-                ],
-            }],
-            client_status: 1, // InProgress
-            server_status: 3, // Done
-            version: 31,
-            github_username: "DimiDumo".to_string(),
-            email_header_max_length: 896,
-            remove_soft_linebreaks: false,
-            stars: 0,
-            ptau: 0,
-            num_local_proofs: 0,
-        };
+        let blueprint = fixtures::subject_extract(ZkFramework::Circom);
 
         // Call the handler with the mock uploader
         let result = process_circuit(blueprint).await;
@@ -910,4 +720,36 @@ mod tests {
         // Assert the result
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_compile_circuit_rsa_key_sizes() {
+        for bits in SUPPORTED_RSA_KEY_BITS {
+            let mut blueprint = fixtures::subject_extract(ZkFramework::Circom);
+            blueprint.rsa_key_bits = Some(bits);
+
+            let result = process_circuit(blueprint).await;
+
+            if let Err(ref e) = result {
+                println!("Error for {} bit keys: {:?}", bits, e);
+            }
+            assert!(result.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compile_circuit_layout_matches_sym() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        blueprint.enable_header_masking = true;
+
+        let result = process_circuit(blueprint.clone()).await;
+
+        if let Err(ref e) = result {
+            println!("Error: {:?}", e);
+        }
+        assert!(result.is_ok());
+
+        let layout = CircuitTemplateInputs::from(blueprint).layout().clone();
+        let sym = fs::read_to_string("./tmp/circuit.sym").unwrap();
+        assert!(verify_sym_layout(&layout, &sym).is_ok());
+    }
 }
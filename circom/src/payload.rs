@@ -1,6 +1,6 @@
-use std::env;
+use std::{collections::HashMap, env};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base64::Engine;
 use dotenv::dotenv;
 use relayer_utils::LOG;
@@ -8,6 +8,8 @@ use sdk_utils::proto_types::proto_blueprint::Blueprint;
 use serde::Deserialize;
 use slog::info;
 
+use crate::verification::VerificationConfig;
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Payload {
@@ -19,6 +21,113 @@ pub struct Payload {
     pub chain_id: u32,
     pub etherscan_api_key: String,
     pub dkim_registry_address: String,
+    /// Derive the zkey beacon from the blueprint and emit a build attestation.
+    #[serde(default)]
+    pub reproducible: bool,
+    /// Deploy the contract behind a UUPS proxy, upgrading the proxy at
+    /// `verifier_contract_address` if the blueprint already has one.
+    #[serde(default)]
+    pub upgradeable: bool,
+    /// Deploy through the CREATE2 factory with a salt derived from the blueprint id and
+    /// version, reusing the contract if it is already deployed at the predicted address.
+    /// The registry address is part of the contract init code, so every chain must use the
+    /// same existing DKIM registry to get the same contract address.
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Deploy with the built-in deployer instead of the Forge deploy scripts. Only plain
+    /// deployments to an existing DKIM registry are supported, neither `deterministic` nor
+    /// `upgradeable`, and source verification still runs `forge verify-contract`.
+    #[serde(default)]
+    pub native: bool,
+    /// Chains to deploy the contract to, replacing `chain_id` and `rpc_url` when not empty.
+    #[serde(default)]
+    pub chains: Vec<ChainTarget>,
+    /// Explorers the deployed contracts are verified on, and how failures are handled.
+    #[serde(default)]
+    pub verification: VerificationConfig,
+}
+
+/// A chain the contract is deployed to.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainTarget {
+    pub chain_id: u32,
+    pub rpc_url: String,
+    /// Overrides the Etherscan API key of the payload on this chain.
+    #[serde(default)]
+    pub etherscan_api_key: Option<String>,
+    /// DKIM registry on this chain, a new registry is deployed if empty.
+    #[serde(default)]
+    pub dkim_registry_address: String,
+}
+
+impl Payload {
+    /// Returns the chains to deploy to, falling back to the single chain of the payload.
+    pub fn chain_targets(&self) -> Result<Vec<ChainTarget>> {
+        if self.chains.is_empty() {
+            return Ok(vec![ChainTarget {
+                chain_id: self.chain_id,
+                rpc_url: self.rpc_url.clone(),
+                etherscan_api_key: None,
+                dkim_registry_address: self.dkim_registry_address.clone(),
+            }]);
+        }
+
+        for (i, target) in self.chains.iter().enumerate() {
+            if target.chain_id == 0 || target.rpc_url.is_empty() {
+                return Err(anyhow!(
+                    "Chain target {} needs a chain id and an RPC URL",
+                    i
+                ));
+            }
+            if self.chains[..i]
+                .iter()
+                .any(|other| other.chain_id == target.chain_id)
+            {
+                return Err(anyhow!(
+                    "Chain {} is listed more than once",
+                    target.chain_id
+                ));
+            }
+        }
+        if self.deterministic
+            && self.chains.iter().any(|target| {
+                !target
+                    .dkim_registry_address
+                    .eq_ignore_ascii_case(&self.chains[0].dkim_registry_address)
+            })
+        {
+            return Err(anyhow!(
+                "Deterministic deployments need the same DKIM registry on every chain"
+            ));
+        }
+        Ok(self.chains.clone())
+    }
+}
+
+impl ChainTarget {
+    /// Points the Forge and cast commands of the deploy stage at this chain.
+    pub fn set_env(&self, default_etherscan_api_key: &str) {
+        env::set_var("RPC_URL", &self.rpc_url);
+        env::set_var("CHAIN_ID", self.chain_id.to_string());
+
+        let etherscan_api_key = self
+            .etherscan_api_key
+            .as_deref()
+            .unwrap_or(default_etherscan_api_key);
+        if etherscan_api_key.is_empty() {
+            env::remove_var("ETHERSCAN_API_KEY");
+        } else {
+            env::set_var("ETHERSCAN_API_KEY", etherscan_api_key);
+        }
+
+        // Registries are per chain, so one chain must not inherit the registry of another
+        if self.dkim_registry_address.is_empty() {
+            env::remove_var("DKIM_REGISTRY");
+        } else {
+            env::set_var("DKIM_REGISTRY", &self.dkim_registry_address);
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -32,17 +141,82 @@ pub struct UploadUrls {
     pub circuit_full_zkey: String,
     pub vk: String,
     pub circuit_zkey: String,
-    pub zkey_b: String,
-    pub zkey_c: String,
-    pub zkey_d: String,
-    pub zkey_e: String,
-    pub zkey_f: String,
-    pub zkey_g: String,
-    pub zkey_h: String,
-    pub zkey_i: String,
-    pub zkey_j: String,
-    pub zkey_k: String,
+    /// Upload URLs for the chunked zkey, keyed by chunk suffix (`b`, `c`, ...).
+    #[serde(default)]
+    pub zkey_chunks: HashMap<String, String>,
+    #[serde(flatten)]
+    pub legacy_zkey_urls: LegacyZkeyUrls,
     pub circom_regex_graphs: String,
+    #[serde(default)]
+    pub manifest: Option<String>,
+    #[serde(default)]
+    pub attestation: Option<String>,
+    #[serde(default)]
+    pub signals: Option<String>,
+    #[serde(default)]
+    pub input_options: Option<String>,
+}
+
+/// Per-chunk upload URLs sent by payloads predating `zkey_chunks`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyZkeyUrls {
+    #[serde(default)]
+    pub zkey_b: Option<String>,
+    #[serde(default)]
+    pub zkey_c: Option<String>,
+    #[serde(default)]
+    pub zkey_d: Option<String>,
+    #[serde(default)]
+    pub zkey_e: Option<String>,
+    #[serde(default)]
+    pub zkey_f: Option<String>,
+    #[serde(default)]
+    pub zkey_g: Option<String>,
+    #[serde(default)]
+    pub zkey_h: Option<String>,
+    #[serde(default)]
+    pub zkey_i: Option<String>,
+    #[serde(default)]
+    pub zkey_j: Option<String>,
+    #[serde(default)]
+    pub zkey_k: Option<String>,
+}
+
+impl UploadUrls {
+    /// Moves the legacy `zkeyB`..`zkeyK` URLs into `zkey_chunks`, so payloads that were not
+    /// migrated yet still upload every chunk.
+    pub fn merge_legacy_zkey_urls(&mut self) -> Result<()> {
+        let legacy = std::mem::take(&mut self.legacy_zkey_urls);
+        let urls = [
+            ("b", legacy.zkey_b),
+            ("c", legacy.zkey_c),
+            ("d", legacy.zkey_d),
+            ("e", legacy.zkey_e),
+            ("f", legacy.zkey_f),
+            ("g", legacy.zkey_g),
+            ("h", legacy.zkey_h),
+            ("i", legacy.zkey_i),
+            ("j", legacy.zkey_j),
+            ("k", legacy.zkey_k),
+        ];
+        for (chunk, url) in urls {
+            let Some(url) = url else { continue };
+            match self.zkey_chunks.get(chunk) {
+                Some(existing) if *existing != url => {
+                    return Err(anyhow!(
+                        "Conflicting upload URLs for zkey chunk '{}' in zkeyChunks and zkey{}",
+                        chunk,
+                        chunk.to_uppercase()
+                    ));
+                }
+                _ => {
+                    self.zkey_chunks.insert(chunk.to_string(), url);
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 // Function to load the payload
@@ -57,7 +231,8 @@ pub fn load_payload() -> Result<Payload> {
     let payload_str = String::from_utf8(decoded_payload)?;
 
     // Deserialize the JSON string into a Payload struct
-    let payload: Payload = serde_json::from_str(&payload_str)?;
+    let mut payload: Payload = serde_json::from_str(&payload_str)?;
+    payload.upload_urls.merge_legacy_zkey_urls()?;
 
     // Setting ENV
     info!(LOG, "Setting ENV variables");
@@ -94,3 +269,90 @@ pub fn load_payload() -> Result<Payload> {
 
     Ok(payload)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk_utils::{fixtures, proto_types::proto_blueprint::ZkFramework};
+
+    fn payload(chains: Vec<ChainTarget>) -> Payload {
+        Payload {
+            blueprint: fixtures::kraken_intermediate(ZkFramework::Circom),
+            upload_urls: serde_json::from_value(serde_json::json!({
+                "circuit": "", "circuitCpp": "", "circuitWasm": "", "witnessCalculator": "",
+                "generateWitness": "", "circuitFullZkey": "", "vk": "", "circuitZkey": "",
+                "circomRegexGraphs": "",
+            }))
+            .unwrap(),
+            database_url: String::new(),
+            private_key: String::new(),
+            rpc_url: "https://sepolia.base.org".to_string(),
+            chain_id: 84532,
+            etherscan_api_key: String::new(),
+            dkim_registry_address: "0x3D3935B3C030893f118a84C92C66dF1B9E4169d6".to_string(),
+            reproducible: false,
+            upgradeable: false,
+            deterministic: false,
+            native: false,
+            chains,
+            verification: VerificationConfig::default(),
+        }
+    }
+
+    fn target(chain_id: u32) -> ChainTarget {
+        ChainTarget {
+            chain_id,
+            rpc_url: format!("https://rpc.example/{}", chain_id),
+            etherscan_api_key: None,
+            dkim_registry_address: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_legacy_zkey_urls() {
+        let mut upload_urls: UploadUrls = serde_json::from_value(serde_json::json!({
+            "circuit": "", "circuitCpp": "", "circuitWasm": "", "witnessCalculator": "",
+            "generateWitness": "", "circuitFullZkey": "", "vk": "", "circuitZkey": "",
+            "circomRegexGraphs": "",
+            "zkeyChunks": { "b": "https://upload/b" },
+            "zkeyB": "https://upload/b",
+            "zkeyC": "https://upload/c",
+        }))
+        .unwrap();
+        upload_urls.merge_legacy_zkey_urls().unwrap();
+        assert_eq!(upload_urls.zkey_chunks.len(), 2);
+        assert_eq!(upload_urls.zkey_chunks["c"], "https://upload/c");
+
+        upload_urls.legacy_zkey_urls.zkey_b = Some("https://other/b".to_string());
+        assert!(upload_urls.merge_legacy_zkey_urls().is_err());
+    }
+
+    #[test]
+    fn test_chain_targets() {
+        let targets = payload(vec![]).chain_targets().unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].chain_id, 84532);
+        assert_eq!(
+            targets[0].dkim_registry_address,
+            "0x3D3935B3C030893f118a84C92C66dF1B9E4169d6"
+        );
+
+        let chains = vec![target(8453), target(84532), target(1)];
+        assert_eq!(payload(chains.clone()).chain_targets().unwrap(), chains);
+
+        assert!(payload(vec![target(8453), target(8453)])
+            .chain_targets()
+            .is_err());
+        assert!(payload(vec![target(0)]).chain_targets().is_err());
+
+        let mut other_registry = target(1);
+        other_registry.dkim_registry_address =
+            "0x0000000000000000000000000000000000000001".to_string();
+        let mut deterministic = payload(vec![target(8453), other_registry]);
+        assert!(deterministic.chain_targets().is_ok());
+        deterministic.deterministic = true;
+        assert!(deterministic.chain_targets().is_err());
+        deterministic.chains[1].dkim_registry_address = String::new();
+        assert!(deterministic.chain_targets().is_ok());
+    }
+}
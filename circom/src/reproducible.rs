@@ -0,0 +1,223 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::Result;
+use sdk_utils::{
+    load_template, proto_types::proto_blueprint::Blueprint, run_command_and_return_output,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    contract::{CONTRACT_TEMPLATE, CONTRACT_TEMPLATE_FILE},
+    template::{CIRCUIT_TEMPLATE, CIRCUIT_TEMPLATE_FILE},
+};
+
+/// Beacon used for the final zkey contribution outside of reproducible mode.
+pub const DEFAULT_BEACON: &str = "0102030405060708090a0b0c0d0e0f101112231415161718221a1b1c1d1e1f";
+
+/// Templates whose contents determine the generated circuit and contract.
+const TEMPLATES: [(&str, &str); 2] = [
+    (CIRCUIT_TEMPLATE_FILE, CIRCUIT_TEMPLATE),
+    (CONTRACT_TEMPLATE_FILE, CONTRACT_TEMPLATE),
+];
+
+/// Build outputs whose hashes are recorded in the attestation.
+const ATTESTED_ARTIFACTS: [&str; 5] = [
+    "circuit.circom",
+    "circuit.r1cs",
+    "circuit.zip",
+    "circuit_full_zkey.zip",
+    "circuit.zkey.gz",
+];
+
+/// Statement that a verification key was derived from a given blueprint,
+/// template version and toolchain.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation {
+    pub blueprint_id: String,
+    pub blueprint_version: i32,
+    pub beacon: String,
+    pub vk_sha256: String,
+    pub template_version: String,
+    pub templates: BTreeMap<String, String>,
+    pub tool_versions: BTreeMap<String, String>,
+    pub artifacts: BTreeMap<String, String>,
+}
+
+/// Derives the zkey beacon from the blueprint id and version, so rebuilding
+/// the same blueprint version always yields the same keys.
+pub fn blueprint_beacon(blueprint: &Blueprint) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(blueprint.id.as_bytes());
+    hasher.update(b":");
+    hasher.update(blueprint.version.to_be_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Writes `attestation.json` into `tmp_dir`. Must run after cleanup, once `vk.json` exists.
+pub async fn write_attestation(tmp_dir: &str, blueprint: &Blueprint, beacon: &str) -> Result<()> {
+    let attestation = build_attestation(tmp_dir, blueprint, beacon, tool_versions(tmp_dir).await)?;
+
+    fs::write(
+        Path::new(tmp_dir).join("attestation.json"),
+        serde_json::to_string_pretty(&attestation)?,
+    )?;
+
+    Ok(())
+}
+
+/// Hashes the verification key, templates and build outputs found in `tmp_dir`.
+fn build_attestation(
+    tmp_dir: &str,
+    blueprint: &Blueprint,
+    beacon: &str,
+    tool_versions: BTreeMap<String, String>,
+) -> Result<Attestation> {
+    let tmp_path = Path::new(tmp_dir);
+
+    let mut templates = BTreeMap::new();
+    let mut template_hasher = Sha256::new();
+    for (file_name, embedded) in TEMPLATES {
+        // Hash the template that was actually rendered, including custom overrides
+        let content = load_template(file_name, embedded)?;
+        template_hasher.update(content.as_bytes());
+        templates.insert(file_name.to_string(), sha256_hex(content.as_bytes()));
+    }
+
+    let mut artifacts = BTreeMap::new();
+    for artifact in ATTESTED_ARTIFACTS {
+        let path = tmp_path.join(artifact);
+        if path.exists() {
+            artifacts.insert(artifact.to_string(), sha256_hex(&fs::read(path)?));
+        }
+    }
+
+    let attestation = Attestation {
+        blueprint_id: blueprint.id.clone(),
+        blueprint_version: blueprint.version,
+        beacon: beacon.to_string(),
+        vk_sha256: sha256_hex(&fs::read(tmp_path.join("vk.json"))?),
+        template_version: hex::encode(template_hasher.finalize()),
+        templates,
+        tool_versions,
+        artifacts,
+    };
+
+    Ok(attestation)
+}
+
+async fn tool_versions(tmp_dir: &str) -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::new();
+    versions.insert(
+        "sdk-circom".to_string(),
+        env!("CARGO_PKG_VERSION").to_string(),
+    );
+
+    for (tool, command, args) in [
+        ("circom", "circom", vec!["--version"]),
+        ("node", "node", vec!["--version"]),
+        ("snarkjs", "npm", vec!["ls", "-g", "snarkjs", "--depth=0"]),
+    ] {
+        let version = run_command_and_return_output(command, &args, None)
+            .await
+            .map(|output| output.trim().to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        versions.insert(tool.to_string(), version);
+    }
+
+    // The chunked snarkjs is pinned by commit in package.json
+    let package_json = Path::new(tmp_dir).join("package.json");
+    if let Some(chunked) = fs::read_to_string(package_json)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| {
+            json["dependencies"]["snarkjs_chunked"]
+                .as_str()
+                .map(String::from)
+        })
+    {
+        versions.insert("snarkjs_chunked".to_string(), chunked);
+    }
+
+    versions
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk_utils::{fixtures, proto_types::proto_blueprint::ZkFramework};
+
+    #[test]
+    fn test_blueprint_beacon() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        let beacon = blueprint_beacon(&blueprint);
+        assert_eq!(beacon.len(), 64);
+        assert_eq!(
+            beacon,
+            blueprint_beacon(&fixtures::kraken_intermediate(ZkFramework::Circom))
+        );
+
+        blueprint.version += 1;
+        assert_ne!(beacon, blueprint_beacon(&blueprint));
+
+        let mut other = fixtures::kraken_intermediate(ZkFramework::Circom);
+        other.id = fixtures::apple_kyc(ZkFramework::Circom).id;
+        assert_ne!(beacon, blueprint_beacon(&other));
+    }
+
+    #[test]
+    fn test_build_attestation() {
+        let tmp_dir = std::env::temp_dir().join(format!("attestation-{}", std::process::id()));
+        fs::create_dir_all(&tmp_dir).unwrap();
+        fs::write(tmp_dir.join("vk.json"), b"{\"protocol\":\"groth16\"}").unwrap();
+        fs::write(tmp_dir.join("circuit.circom"), b"pragma circom 2.1.6;").unwrap();
+
+        let blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        let beacon = blueprint_beacon(&blueprint);
+        let tool_versions = BTreeMap::from([("circom".to_string(), "2.1.9".to_string())]);
+        let attestation = build_attestation(
+            tmp_dir.to_str().unwrap(),
+            &blueprint,
+            &beacon,
+            tool_versions,
+        )
+        .unwrap();
+        fs::remove_dir_all(&tmp_dir).unwrap();
+
+        assert_eq!(attestation.blueprint_id, blueprint.id);
+        assert_eq!(attestation.blueprint_version, blueprint.version);
+        assert_eq!(attestation.beacon, beacon);
+        assert_eq!(
+            attestation.vk_sha256,
+            sha256_hex(b"{\"protocol\":\"groth16\"}")
+        );
+
+        // Only outputs present on disk are attested
+        assert_eq!(
+            attestation.artifacts,
+            BTreeMap::from([(
+                "circuit.circom".to_string(),
+                sha256_hex(b"pragma circom 2.1.6;")
+            )])
+        );
+
+        assert_eq!(attestation.templates.len(), TEMPLATES.len());
+        assert_eq!(
+            attestation.templates[CIRCUIT_TEMPLATE_FILE],
+            sha256_hex(CIRCUIT_TEMPLATE.as_bytes())
+        );
+        let mut template_hasher = Sha256::new();
+        template_hasher.update(CIRCUIT_TEMPLATE.as_bytes());
+        template_hasher.update(CONTRACT_TEMPLATE.as_bytes());
+        assert_eq!(
+            attestation.template_version,
+            hex::encode(template_hasher.finalize())
+        );
+        assert_eq!(attestation.tool_versions["circom"], "2.1.9");
+    }
+}
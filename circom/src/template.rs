@@ -1,14 +1,19 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use regex::Regex;
 use sdk_utils::{
-    compute_signal_length,
+    load_template,
     proto_types::proto_blueprint::{Blueprint, DecomposedRegex},
+    CircuitPlan, ExternalInputType, InputGenerationOptions, PublicSignalLayout, RegexLocation,
 };
 use serde::Serialize;
-use std::fs;
+use std::{fs, path::Path};
 use tera::{Context, Tera};
 use zk_regex_compiler::{gen_from_decomposed, DecomposedRegexConfig, ProvingFramework, RegexPart};
 
+pub const CIRCUIT_TEMPLATE_FILE: &str = "template.circom.tera";
+/// Circuit template compiled into the binary, overridable through `$TEMPLATES_DIR`.
+pub const CIRCUIT_TEMPLATE: &str = include_str!("../templates/template.circom.tera");
+
 /// Represents a single decomposed regex, along with computed fields
 /// used for generating the circuit template.
 #[derive(Serialize)]
@@ -24,6 +29,7 @@ struct RegexEntry {
     num_public_parts: usize,
     public_parts_max_length: Vec<usize>,
     is_hashed: bool,
+    is_hash_salted: bool,
     regex_idx_name: String,
     num_reveal_signals: i32,
     signal_regex_out_string: String,
@@ -33,8 +39,11 @@ struct RegexEntry {
 #[derive(Serialize)]
 struct ExternalInputEntry {
     name: String,
+    input_type: ExternalInputType,
+    is_array: bool,
     max_length: usize,
     signal_length: usize,
+    is_committed: bool,
 }
 
 /// A struct that holds all the data required to render the circuit template.
@@ -47,160 +56,221 @@ pub struct CircuitTemplateInputs {
     enable_header_masking: bool,
     enable_body_masking: bool,
     remove_soft_linebreaks: bool,
+    rsa_key_bits: usize,
+    rsa_n: usize,
+    rsa_k: usize,
+    split_rsa_hash: bool,
+    enable_nullifier: bool,
+    sha_precompute_selector: Option<String>,
     regexes: Vec<RegexEntry>,
     external_inputs: Vec<ExternalInputEntry>,
-    public_args_string: String,
+    public_inputs: Vec<String>,
+    #[serde(skip)]
+    layout: PublicSignalLayout,
+    #[serde(skip)]
+    input_generation_options: InputGenerationOptions,
 }
 
 impl From<Blueprint> for CircuitTemplateInputs {
     fn from(value: Blueprint) -> Self {
-        let circuit_name = value.circuit_name;
-        let email_header_max_length = value.email_header_max_length as usize;
-        let email_body_max_length = value.email_body_max_length as usize;
-        let ignore_body_hash_check = value.ignore_body_hash_check;
-        let enable_header_masking = value.enable_header_masking;
-        let enable_body_masking = value.enable_body_masking;
-        let remove_soft_linebreaks = value.remove_soft_linebreaks;
-
-        // Process regexes
-        let mut regexes = Vec::new();
-
-        for regex in value.decomposed_regexes {
-            let name = regex.name.clone();
-            let uppercased_name = name.to_uppercase();
-            let max_match_length = regex.max_match_length as usize;
-            let regex_circuit_name = format!("{}_regex", regex.name);
-
-            // Determine location and its max length
-            let (location, max_length_of_location, max_length_of_location_name) =
-                if regex.location == "header" {
-                    (
-                        "emailHeader".to_string(),
-                        email_header_max_length as usize,
-                        "maxHeaderLength".to_string(),
-                    )
-                } else if remove_soft_linebreaks {
-                    (
-                        "decodedEmailBodyIn".to_string(),
-                        email_body_max_length as usize,
-                        "maxBodyLength".to_string(),
-                    )
-                } else {
-                    (
-                        "emailBody".to_string(),
-                        email_body_max_length as usize,
-                        "maxBodyLength".to_string(),
-                    )
-                };
+        CircuitTemplateInputs::from(&CircuitPlan::from(&value))
+    }
+}
 
-            // Compute reveal and indexing strings
-            let mut reveal_string = String::new();
-            let mut num_public_parts = 0;
-            let mut public_parts_max_length = Vec::new();
-            let is_hashed = regex.is_hashed.unwrap_or(false);
-            let mut regex_idx_name = String::new();
-            let mut num_reveal_signals: i32 = -1;
-            let mut signal_regex_out_string = String::new();
-
-            for part in &regex.parts {
-                if part.is_public == Some(true) {
-                    num_reveal_signals += 1;
-                    if regex_idx_name.is_empty() {
-                        regex_idx_name = format!("{}RegexIdx", name);
-                    } else {
-                        regex_idx_name
-                            .push_str(&format!(", {}RegexIdx{}", name, num_reveal_signals));
-                    }
+impl From<&CircuitPlan> for CircuitTemplateInputs {
+    fn from(plan: &CircuitPlan) -> Self {
+        let regexes = plan
+            .regexes
+            .iter()
+            .map(|regex| {
+                let name = regex.name.clone();
 
-                    num_public_parts += 1;
-                    public_parts_max_length.push(part.max_length() as usize);
+                // Map the location to the signal holding it
+                let (location, max_length_of_location_name) = match regex.location {
+                    RegexLocation::Header => ("emailHeader", "maxHeaderLength"),
+                    RegexLocation::DecodedBody => ("decodedEmailBodyIn", "maxBodyLength"),
+                    RegexLocation::Body => ("emailBody", "maxBodyLength"),
+                };
 
-                    if num_reveal_signals == 0 {
-                        signal_regex_out_string.push_str(&format!(
-                            ", {}RegexReveal[{}]",
-                            name,
-                            part.max_length()
-                        ));
-                    } else {
-                        signal_regex_out_string.push_str(&format!(
-                            ", {}RegexReveal{}[{}]",
-                            name,
-                            num_reveal_signals,
-                            part.max_length()
-                        ));
-                    }
+                // Compute reveal and indexing strings
+                let mut reveal_string = String::new();
+                let mut regex_idx_name = String::new();
+                let mut signal_regex_out_string = String::new();
 
-                    if reveal_string.is_empty() {
-                        reveal_string.push_str(&format!(", {}RegexReveal", name));
+                for (i, capture) in regex.captures.iter().enumerate() {
+                    let suffix = if i == 0 { String::new() } else { i.to_string() };
+                    if i == 0 {
+                        regex_idx_name = format!("{}RegexIdx", name);
                     } else {
-                        reveal_string
-                            .push_str(&format!(", {}RegexReveal{}", name, num_reveal_signals));
+                        regex_idx_name.push_str(&format!(", {}RegexIdx{}", name, suffix));
                     }
+                    signal_regex_out_string.push_str(&format!(
+                        ", {}RegexReveal{}[{}]",
+                        name, suffix, capture.max_length
+                    ));
+                    reveal_string.push_str(&format!(", {}RegexReveal{}", name, suffix));
                 }
-            }
 
-            // Increment once more to account for indexing
-            num_reveal_signals += 1;
-
-            regexes.push(RegexEntry {
-                name,
-                uppercased_name,
-                max_match_length,
-                regex_circuit_name,
-                location,
-                max_length_of_location,
-                max_length_of_location_name,
-                reveal_string,
-                num_public_parts,
-                public_parts_max_length,
-                is_hashed,
-                regex_idx_name,
-                num_reveal_signals,
-                signal_regex_out_string,
-            });
-        }
+                RegexEntry {
+                    uppercased_name: name.to_uppercase(),
+                    regex_circuit_name: format!("{}_regex", name),
+                    name,
+                    max_match_length: regex.max_match_length,
+                    location: location.to_string(),
+                    max_length_of_location: regex.max_length_of_location,
+                    max_length_of_location_name: max_length_of_location_name.to_string(),
+                    reveal_string,
+                    num_public_parts: regex.captures.len(),
+                    public_parts_max_length: regex
+                        .captures
+                        .iter()
+                        .map(|capture| capture.max_length)
+                        .collect(),
+                    is_hashed: regex.is_hashed,
+                    is_hash_salted: regex.is_hashed && regex.is_hash_salted,
+                    regex_idx_name,
+                    num_reveal_signals: regex.captures.len() as i32,
+                    signal_regex_out_string,
+                }
+            })
+            .collect();
 
-        // Process external inputs
-        let external_inputs: Vec<ExternalInputEntry> = value
+        let external_inputs: Vec<ExternalInputEntry> = plan
             .external_inputs
             .iter()
-            .map(|input| {
-                let signal_length = compute_signal_length(input.max_length as usize);
-                ExternalInputEntry {
-                    name: input.name.clone(),
-                    max_length: input.max_length as usize,
-                    signal_length,
-                }
+            .map(|input| ExternalInputEntry {
+                name: input.name.clone(),
+                input_type: input.input_type,
+                is_array: input.input_type.is_array(),
+                max_length: input.max_length,
+                signal_length: input.packed_width,
+                is_committed: input.is_committed,
             })
             .collect();
 
-        // Compute the public args string from external inputs
-        let public_args: Vec<String> = external_inputs.iter().map(|i| i.name.clone()).collect();
-        let mut public_args_string = public_args.join(", ");
-        if !public_args_string.is_empty() {
-            public_args_string.insert_str(0, ", ");
-        }
+        let (rsa_n, rsa_k) = rsa_limbs(plan.rsa_key_bits);
+
+        // The public list of the main component follows the shared signal layout
+        let layout = PublicSignalLayout::circom(plan);
+        let public_inputs = layout
+            .public_inputs()
+            .map(|signal| signal.name.clone())
+            .collect();
 
         CircuitTemplateInputs {
-            circuit_name,
-            email_header_max_length,
-            email_body_max_length,
-            ignore_body_hash_check,
-            enable_header_masking,
-            enable_body_masking,
-            remove_soft_linebreaks,
+            circuit_name: plan.circuit_name.clone(),
+            email_header_max_length: plan.email_header_max_length,
+            email_body_max_length: plan.email_body_max_length,
+            ignore_body_hash_check: plan.ignore_body_hash_check,
+            enable_header_masking: plan.enable_header_masking,
+            enable_body_masking: plan.enable_body_masking,
+            remove_soft_linebreaks: plan.remove_soft_linebreaks,
+            rsa_key_bits: plan.rsa_key_bits,
+            rsa_n,
+            rsa_k,
+            split_rsa_hash: rsa_k > POSEIDON_LARGE_MAX_CHUNKS,
+            enable_nullifier: plan.enable_nullifier,
+            sha_precompute_selector: plan.sha_precompute_selector.clone(),
             regexes,
             external_inputs,
-            public_args_string,
+            public_inputs,
+            layout,
+            input_generation_options: plan.input_generation_options(),
         }
     }
 }
 
+/// Largest number of chunks `PoseidonLarge` accepts; it also requires more than 16.
+const POSEIDON_LARGE_MAX_CHUNKS: usize = 32;
+
+/// Splits an RSA key into `k` limbs of `n` bits, keeping `n * k` just above the key size.
+/// 1024 bit keys use 60 bit limbs so `k` reaches the 17 chunks `PoseidonLarge` needs. 4096 bit
+/// keys need 34 limbs, so their pubkey hash and nullifier hash both halves separately.
+fn rsa_limbs(rsa_key_bits: usize) -> (usize, usize) {
+    match rsa_key_bits {
+        1024 => (60, 18),
+        2048 => (121, 17),
+        4096 => (121, 34),
+        bits => (121, bits / 121 + 1),
+    }
+}
+
+impl CircuitTemplateInputs {
+    /// Returns the public signals of the rendered circuit.
+    pub fn layout(&self) -> &PublicSignalLayout {
+        &self.layout
+    }
+
+    pub fn input_generation_options(&self) -> &InputGenerationOptions {
+        &self.input_generation_options
+    }
+}
+
+/// Writes the options needed to generate circuit inputs to `input_options.json` in `out_dir`.
+pub fn write_input_options_json(options: &InputGenerationOptions, out_dir: &Path) -> Result<()> {
+    fs::write(
+        out_dir.join("input_options.json"),
+        serde_json::to_string_pretty(options)?,
+    )?;
+    Ok(())
+}
+
+/// Writes the public signal layout to `signals.json` in `out_dir`.
+pub fn write_signals_json(layout: &PublicSignalLayout, out_dir: &Path) -> Result<()> {
+    fs::write(
+        out_dir.join("signals.json"),
+        serde_json::to_string_pretty(layout)?,
+    )?;
+    Ok(())
+}
+
+/// Checks the public signals recorded in a circom `.sym` file against the layout.
+/// Public signals occupy witness indices `1..=layout.len()`, in order.
+pub fn verify_sym_layout(layout: &PublicSignalLayout, sym: &str) -> Result<()> {
+    let mut public_signals = vec![None; layout.len()];
+    for line in sym.lines() {
+        let columns: Vec<&str> = line.splitn(4, ',').collect();
+        let [_, witness_idx, _, name] = columns[..] else {
+            continue;
+        };
+        let Ok(witness_idx) = witness_idx.parse::<usize>() else {
+            continue;
+        };
+        let Some(name) = name.strip_prefix("main.") else {
+            continue;
+        };
+        if witness_idx == 0 || witness_idx > layout.len() || name.contains('.') {
+            continue;
+        }
+        let name = name.split('[').next().unwrap_or(name);
+        public_signals[witness_idx - 1] = Some(name.to_string());
+    }
+
+    for signal in &layout.signals {
+        let range = signal.start_idx..signal.start_idx + signal.width;
+        for (idx, found) in range.clone().zip(&public_signals[range]) {
+            if found.as_deref() != Some(signal.name.as_str()) {
+                return Err(anyhow!(
+                    "Public signal {} should be {}, but the circuit has {}",
+                    idx,
+                    signal.name,
+                    found.as_deref().unwrap_or("nothing")
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Generates a CIRCOM circuit file by rendering a Tera template with the provided inputs.
 /// After rendering, consecutive newlines are collapsed into a single newline.
 pub fn generate_circuit(circuit_template_input: CircuitTemplateInputs) -> Result<String> {
     let mut tera = Tera::default();
-    tera.add_template_file("./templates/template.circom.tera", Some("circuit.circom"))?;
+    tera.add_raw_template(
+        "circuit.circom",
+        &load_template(CIRCUIT_TEMPLATE_FILE, CIRCUIT_TEMPLATE)?,
+    )?;
 
     let mut context = Context::new();
     context.insert("circuit_name", &circuit_template_input.circuit_name);
@@ -228,12 +298,18 @@ pub fn generate_circuit(circuit_template_input: CircuitTemplateInputs) -> Result
         "remove_soft_linebreaks",
         &circuit_template_input.remove_soft_linebreaks,
     );
-    context.insert("regexes", &circuit_template_input.regexes);
-    context.insert("external_inputs", &circuit_template_input.external_inputs);
+    context.insert("rsa_key_bits", &circuit_template_input.rsa_key_bits);
+    context.insert("rsa_n", &circuit_template_input.rsa_n);
+    context.insert("rsa_k", &circuit_template_input.rsa_k);
+    context.insert("split_rsa_hash", &circuit_template_input.split_rsa_hash);
+    context.insert("enable_nullifier", &circuit_template_input.enable_nullifier);
     context.insert(
-        "public_args_string",
-        &circuit_template_input.public_args_string,
+        "sha_precompute_selector",
+        &circuit_template_input.sha_precompute_selector,
     );
+    context.insert("regexes", &circuit_template_input.regexes);
+    context.insert("external_inputs", &circuit_template_input.external_inputs);
+    context.insert("public_inputs", &circuit_template_input.public_inputs);
 
     let circuit = tera.render("circuit.circom", &context)?;
 
@@ -241,8 +317,11 @@ pub fn generate_circuit(circuit_template_input: CircuitTemplateInputs) -> Result
     Ok(re.replace_all(&circuit, "\n").to_string())
 }
 
-/// Generates CIRCOM files for the provided decomposed regexes.
-pub fn generate_regex_circuits(decomposed_regexes: Vec<DecomposedRegex>) -> Result<()> {
+/// Generates CIRCOM files for the provided decomposed regexes into `regex_dir`.
+pub fn generate_regex_circuits(
+    decomposed_regexes: Vec<DecomposedRegex>,
+    regex_dir: &Path,
+) -> Result<()> {
     for decomposed_regex in decomposed_regexes {
         let mut decomposed_regex_config = Vec::new();
         for part in decomposed_regex.parts.clone() {
@@ -263,11 +342,290 @@ pub fn generate_regex_circuits(decomposed_regexes: Vec<DecomposedRegex>) -> Resu
         let (graph, code) =
             gen_from_decomposed(config, &decomposed_regex.name, ProvingFramework::Circom)?;
 
-        let file_path = format!("./tmp/regex/{}_regex.circom", decomposed_regex.name);
+        let file_path = regex_dir.join(format!("{}_regex.circom", decomposed_regex.name));
         fs::write(file_path, code)?;
-        let graph_path = format!("./tmp/regex/{}_regex.json", decomposed_regex.name);
+        let graph_path = regex_dir.join(format!("{}_regex.json", decomposed_regex.name));
         fs::write(graph_path, serde_json::to_string(&graph)?)?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk_utils::{
+        fixtures,
+        proto_types::proto_blueprint::{
+            ExternalInput, ExternalInputType as ProtoExternalInputType, ZkFramework,
+        },
+        SignalEncoding, SignalKind, SUPPORTED_RSA_KEY_BITS,
+    };
+
+    /// Compares the rendered circuit with `snapshots/<name>.circom`.
+    fn assert_circuit_snapshot(name: &str, blueprint: Blueprint) {
+        let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("snapshots")
+            .join(format!("{}.circom", name));
+        fixtures::assert_snapshot(&path, &circuit);
+    }
+
+    #[test]
+    fn test_snapshot_x_export_data() {
+        assert_circuit_snapshot(
+            "x_export_data",
+            fixtures::x_export_data(ZkFramework::Circom),
+        );
+    }
+
+    #[test]
+    fn test_snapshot_x_export_data_masked() {
+        let mut blueprint = fixtures::x_export_data(ZkFramework::Circom);
+        blueprint.enable_header_masking = true;
+        blueprint.enable_body_masking = true;
+        assert_circuit_snapshot("x_export_data_masked", blueprint);
+    }
+
+    #[test]
+    fn test_snapshot_apple_kyc() {
+        assert_circuit_snapshot("apple_kyc", fixtures::apple_kyc(ZkFramework::Circom));
+    }
+
+    #[test]
+    fn test_snapshot_succinct_residency() {
+        assert_circuit_snapshot(
+            "succinct_residency",
+            fixtures::succinct_residency(ZkFramework::Circom),
+        );
+    }
+
+    #[test]
+    fn test_snapshot_kraken_intermediate() {
+        assert_circuit_snapshot(
+            "kraken_intermediate",
+            fixtures::kraken_intermediate(ZkFramework::Circom),
+        );
+    }
+
+    #[test]
+    fn test_snapshot_subject_extract() {
+        assert_circuit_snapshot(
+            "subject_extract",
+            fixtures::subject_extract(ZkFramework::Circom),
+        );
+    }
+
+    #[test]
+    fn test_template_inputs_hashed_multi_part_regex() {
+        let inputs =
+            CircuitTemplateInputs::from(fixtures::kraken_intermediate(ZkFramework::Circom));
+        let regex = &inputs.regexes[0];
+
+        assert!(regex.is_hashed);
+        assert_eq!(regex.num_public_parts, 2);
+        assert_eq!(regex.num_reveal_signals, 2);
+        assert_eq!(regex.public_parts_max_length, vec![20, 20]);
+        assert_eq!(
+            regex.reveal_string,
+            ", EmailSubjectRegexReveal, EmailSubjectRegexReveal1"
+        );
+        assert_eq!(inputs.external_inputs[0].signal_length, 133);
+        assert_eq!(inputs.public_inputs, vec!["proverETHAddress", "test"]);
+    }
+
+    #[test]
+    fn test_salted_regex_hash() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        blueprint.decomposed_regexes[0].is_hash_salted = Some(true);
+
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        let hash = inputs.layout().signal("EmailSubjectPackedHash").unwrap();
+        assert_eq!(
+            hash.kind,
+            SignalKind::RegexHash {
+                regex: "EmailSubject".to_string(),
+                salted: true,
+            }
+        );
+        assert_eq!(hash.encoding, SignalEncoding::PoseidonHash);
+
+        let circuit = generate_circuit(inputs).unwrap();
+        assert!(circuit.contains(
+            "var EmailSubjectTotalPackedLength = computeIntChunkLength(EmailSubjectMaxLength) * 2 + 1;"
+        ));
+        assert!(circuit.contains("signal input EmailSubjectHashSalt;"));
+        assert!(circuit.contains(
+            "EmailSubjectAllPackedOut[EmailSubjectTotalPackedLength - 1] <== EmailSubjectHashSalt;"
+        ));
+    }
+
+    #[test]
+    fn test_committed_external_input() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        blueprint.external_inputs[0].is_committed = Some(true);
+
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        assert_eq!(inputs.public_inputs, vec!["proverETHAddress"]);
+        let commitment = inputs.layout().signal("testCommitment").unwrap();
+        assert_eq!(commitment.start_idx, inputs.layout().len() - 2);
+
+        let circuit = generate_circuit(inputs).unwrap();
+        assert!(circuit.contains("signal input test[133];\n    signal input testSalt;"));
+        assert!(circuit.contains("testCommitmentInputs[133] <== testSalt;"));
+        assert!(circuit.contains(
+            "signal output testCommitment <== PoseidonModular(133 + 1)(testCommitmentInputs);"
+        ));
+        assert!(circuit.contains("component main { public [proverETHAddress] }"));
+    }
+
+    #[test]
+    fn test_typed_external_inputs() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Circom);
+        for (name, input_type) in [
+            ("recipient", ProtoExternalInputType::Address),
+            ("amount", ProtoExternalInputType::Uint256),
+            ("digest", ProtoExternalInputType::Bytes32),
+            ("accepted", ProtoExternalInputType::Bool),
+        ] {
+            blueprint.external_inputs.push(ExternalInput {
+                name: name.to_string(),
+                input_type: Some(input_type as i32),
+                is_committed: Some(name == "amount"),
+                ..Default::default()
+            });
+        }
+
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        assert_eq!(
+            inputs.public_inputs,
+            vec![
+                "proverETHAddress",
+                "test",
+                "recipient",
+                "digest",
+                "accepted"
+            ]
+        );
+        let digest = inputs.layout().signal("digest").unwrap();
+        assert_eq!(digest.width, 2);
+        assert_eq!(digest.encoding, SignalEncoding::Bytes32);
+
+        let circuit = generate_circuit(inputs).unwrap();
+        assert!(circuit.contains("signal input test[133];"));
+        assert!(circuit.contains("signal input recipient;\n    _ <== Num2Bits(160)(recipient);"));
+        assert!(circuit.contains("signal input digest[2];\n    _ <== Num2Bits(128)(digest[0]);"));
+        assert!(circuit.contains("accepted * (accepted - 1) === 0;"));
+        assert!(circuit.contains("amountCommitmentInputs[0] <== amount;"));
+        assert!(circuit.contains("signal output amountCommitment <== PoseidonModular(1 + 1)"));
+    }
+
+    #[test]
+    fn test_rsa_key_size() {
+        assert_eq!(rsa_limbs(1024), (60, 18));
+        assert_eq!(rsa_limbs(2048), (121, 17));
+        assert_eq!(rsa_limbs(4096), (121, 34));
+        for bits in SUPPORTED_RSA_KEY_BITS {
+            let (n, k) = rsa_limbs(bits as usize);
+            let mut blueprint = fixtures::subject_extract(ZkFramework::Circom);
+            blueprint.enable_nullifier = Some(true);
+            blueprint.rsa_key_bits = Some(bits);
+            let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+
+            assert!(n * k > bits as usize);
+            assert!(n < 127);
+            assert!(circuit.contains(&format!("assert(n * k > {});", bits)));
+            assert!(circuit.contains(&format!(", {}, {}, 7);", n, k)));
+
+            // Every PoseidonLarge instance must get 17 to 32 chunks
+            let chunks = if k > POSEIDON_LARGE_MAX_CHUNKS {
+                assert!(circuit.contains("pubkeyHash <== SplitPoseidonLarge(n, k)(pubkey);"));
+                assert!(circuit.contains("Poseidon(1)([SplitPoseidonLarge(n, k)(signature)])"));
+                vec![k / 2, k - k / 2]
+            } else {
+                assert!(circuit.contains("pubkeyHash <== EV.pubkeyHash;"));
+                assert!(circuit.contains("EmailNullifier(n, k)(signature);"));
+                assert!(!circuit.contains("SplitPoseidonLarge"));
+                vec![k]
+            };
+            assert!(chunks
+                .iter()
+                .all(|chunks| (17..=POSEIDON_LARGE_MAX_CHUNKS).contains(chunks)));
+        }
+    }
+
+    #[test]
+    fn test_nullifier_output() {
+        let mut blueprint = fixtures::subject_extract(ZkFramework::Circom);
+        blueprint.enable_nullifier = Some(true);
+
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        let nullifier = inputs.layout().signal("emailNullifier").unwrap();
+        assert_eq!(
+            nullifier.start_idx,
+            inputs.layout().signal("headerHashLo").unwrap().start_idx + 1
+        );
+
+        // signals.json marks the nullifier as specific to Circom proofs
+        let json = serde_json::to_value(nullifier).unwrap();
+        assert_eq!(json["kind"], "emailNullifier");
+        assert_eq!(json["framework"], "circom");
+
+        let circuit = generate_circuit(inputs).unwrap();
+        assert!(circuit.contains("include \"@zk-email/circuits/helpers/email-nullifier.circom\";"));
+        assert!(
+            circuit.contains("signal output emailNullifier <== EmailNullifier(n, k)(signature);")
+        );
+    }
+
+    #[test]
+    fn test_sha_precompute_selector() {
+        let mut blueprint = fixtures::x_export_data(ZkFramework::Circom);
+        blueprint.sha_precompute_selector = "<div id=\"content\">".to_string();
+
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        assert_eq!(
+            inputs
+                .input_generation_options()
+                .sha_precompute_selector
+                .as_deref(),
+            Some("<div id=\"content\">")
+        );
+
+        let circuit = generate_circuit(inputs).unwrap();
+        assert!(circuit.contains(
+            "signal input precomputedSHA[32]; // SHA-256 state of the body up to the selector \"<div id=\"content\">\""
+        ));
+    }
+
+    #[test]
+    fn test_verify_sym_layout() {
+        let inputs = CircuitTemplateInputs::from(fixtures::subject_extract(ZkFramework::Circom));
+        let layout = inputs.layout();
+
+        // One .sym line per public signal element, followed by a private input and a subcomponent signal
+        let mut sym = String::new();
+        let mut witness_idx = 1;
+        for signal in &layout.signals {
+            for i in 0..signal.width {
+                sym.push_str(&format!(
+                    "{},{},0,main.{}[{}]\n",
+                    witness_idx, witness_idx, signal.name, i
+                ));
+                witness_idx += 1;
+            }
+        }
+        sym.push_str(&format!(
+            "{},{},0,main.emailHeaderLength\n",
+            witness_idx, witness_idx
+        ));
+        sym.push_str("999,-1,1,main.EV.pubkeyHash\n");
+        assert!(verify_sym_layout(layout, &sym).is_ok());
+
+        let swapped = sym
+            .replacen("main.headerHashHi", "main.tmp", 1)
+            .replacen("main.headerHashLo", "main.headerHashHi", 1)
+            .replacen("main.tmp", "main.headerHashLo", 1);
+        assert!(verify_sym_layout(layout, &swapped).is_err());
+    }
+}
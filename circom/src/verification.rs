@@ -0,0 +1,366 @@
+//! Source verification of deployed contracts on block explorers through `forge verify-contract`.
+
+use std::{env, time::Duration};
+
+use anyhow::{anyhow, Result};
+use relayer_utils::LOG;
+use sdk_utils::run_command;
+use serde::Deserialize;
+use slog::{info, warn};
+
+use crate::deployment::DeploymentResult;
+
+/// A block explorer contracts are verified on.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Explorer {
+    /// Etherscan or one of its forks, using `$ETHERSCAN_API_KEY` unless a key is given.
+    #[serde(rename_all = "camelCase")]
+    Etherscan {
+        #[serde(default)]
+        api_key: Option<String>,
+        #[serde(default)]
+        url: Option<String>,
+    },
+    Blockscout {
+        url: String,
+    },
+    /// Sourcify, the public instance unless a server URL is given.
+    Sourcify {
+        #[serde(default)]
+        url: Option<String>,
+    },
+}
+
+impl Explorer {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Explorer::Etherscan { .. } => "etherscan",
+            Explorer::Blockscout { .. } => "blockscout",
+            Explorer::Sourcify { .. } => "sourcify",
+        }
+    }
+}
+
+/// Where and how persistently deployed contracts are verified.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VerificationConfig {
+    /// Explorers to verify on, Etherscan if `$ETHERSCAN_API_KEY` is set when empty.
+    pub explorers: Vec<Explorer>,
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled after every further attempt.
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    /// Fail the build when a contract cannot be verified. The deployment is recorded either
+    /// way, see `check_verifications`.
+    pub fatal: bool,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        VerificationConfig {
+            explorers: Vec::new(),
+            max_attempts: 3,
+            initial_delay_secs: 10,
+            max_delay_secs: 60,
+            fatal: true,
+        }
+    }
+}
+
+impl VerificationConfig {
+    /// Returns the explorers to verify on, falling back to Etherscan with the API key of
+    /// the chain being deployed to.
+    pub fn explorers(&self) -> Vec<Explorer> {
+        if self.explorers.is_empty() && env::var("ETHERSCAN_API_KEY").is_ok() {
+            return vec![Explorer::Etherscan {
+                api_key: None,
+                url: None,
+            }];
+        }
+        self.explorers.clone()
+    }
+
+    /// Delay after the failed `attempt`, counted from 1.
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        Duration::from_secs(
+            self.initial_delay_secs
+                .saturating_mul(factor)
+                .min(self.max_delay_secs),
+        )
+    }
+}
+
+/// A deployed contract along with what its source is verified against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractToVerify {
+    pub name: String,
+    pub address: String,
+    /// Source file and contract name, e.g. `tmp/Contract.sol:Contract`.
+    pub source: String,
+    /// Hex encoded constructor arguments, if the contract takes any.
+    pub constructor_args: Option<String>,
+}
+
+/// Outcome of verifying a contract on one explorer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationResult {
+    pub contract: String,
+    pub explorer: &'static str,
+    pub verified: bool,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+/// Builds the `forge verify-contract` arguments verifying `contract` on `explorer`.
+pub fn verify_args(explorer: &Explorer, chain_id: u32, contract: &ContractToVerify) -> Vec<String> {
+    let mut args = vec![
+        "verify-contract".to_string(),
+        "--chain-id".to_string(),
+        chain_id.to_string(),
+        "--verifier".to_string(),
+        explorer.name().to_string(),
+    ];
+    match explorer {
+        Explorer::Etherscan { api_key, url } => {
+            if let Some(api_key) = api_key {
+                args.extend(["--etherscan-api-key".to_string(), api_key.clone()]);
+            }
+            if let Some(url) = url {
+                args.extend(["--verifier-url".to_string(), url.clone()]);
+            }
+        }
+        Explorer::Blockscout { url } => {
+            args.extend(["--verifier-url".to_string(), url.clone()]);
+        }
+        Explorer::Sourcify { url } => {
+            if let Some(url) = url {
+                args.extend(["--verifier-url".to_string(), url.clone()]);
+            }
+        }
+    }
+    if let Some(constructor_args) = &contract.constructor_args {
+        args.extend(["--constructor-args".to_string(), constructor_args.clone()]);
+    }
+    args.extend([contract.address.clone(), contract.source.clone()]);
+    args
+}
+
+/// Verifies every contract on every explorer, retrying with backoff. Failures are returned
+/// as results, so the contracts already on-chain are recorded before the build can fail.
+pub async fn verify_contracts(
+    config: &VerificationConfig,
+    chain_id: u32,
+    contracts: &[ContractToVerify],
+) -> Vec<VerificationResult> {
+    let mut results = Vec::new();
+    for explorer in config.explorers() {
+        for contract in contracts {
+            let result = verify_contract(config, &explorer, chain_id, contract).await;
+            if let Some(error) = &result.error {
+                warn!(
+                    LOG,
+                    "Could not verify {} on {}: {}",
+                    contract.name,
+                    explorer.name(),
+                    error
+                );
+            }
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// Fails if any contract of `deployments` could not be verified and the config makes
+/// verification failures fatal.
+pub fn check_verifications(
+    config: &VerificationConfig,
+    deployments: &[DeploymentResult],
+) -> Result<()> {
+    let failed: Vec<String> = deployments
+        .iter()
+        .flat_map(|deployment| {
+            deployment
+                .verifications
+                .iter()
+                .filter(|result| !result.verified)
+                .map(|result| {
+                    format!(
+                        "{} on {} (chain {}, {} attempts): {}",
+                        result.contract,
+                        result.explorer,
+                        deployment.chain_id,
+                        result.attempts,
+                        result.error.as_deref().unwrap_or("unknown error")
+                    )
+                })
+        })
+        .collect();
+
+    if config.fatal && !failed.is_empty() {
+        return Err(anyhow!("Failed to verify contracts: {}", failed.join("; ")));
+    }
+    Ok(())
+}
+
+async fn verify_contract(
+    config: &VerificationConfig,
+    explorer: &Explorer,
+    chain_id: u32,
+    contract: &ContractToVerify,
+) -> VerificationResult {
+    let args = verify_args(explorer, chain_id, contract);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let mut last_error = None;
+    let mut attempts = 0;
+    for attempt in 1..=config.max_attempts.max(1) {
+        attempts = attempt;
+        info!(
+            LOG,
+            "Attempting to verify {} on {} (attempt {}/{})",
+            contract.name,
+            explorer.name(),
+            attempt,
+            config.max_attempts
+        );
+        match run_command("forge", &args, None).await {
+            Ok(_) => {
+                info!(
+                    LOG,
+                    "Successfully verified {} on {}",
+                    contract.name,
+                    explorer.name()
+                );
+                last_error = None;
+                break;
+            }
+            Err(e) => {
+                last_error = Some(e.to_string());
+                if attempt < config.max_attempts {
+                    let delay = config.backoff_delay(attempt);
+                    info!(
+                        LOG,
+                        "Verification failed: {}, retrying in {}s",
+                        e,
+                        delay.as_secs()
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    VerificationResult {
+        contract: contract.name.clone(),
+        explorer: explorer.name(),
+        verified: last_error.is_none(),
+        attempts,
+        error: last_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract() -> ContractToVerify {
+        ContractToVerify {
+            name: "Contract".to_string(),
+            address: "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0".to_string(),
+            source: "tmp/Contract.sol:Contract".to_string(),
+            constructor_args: Some("0x01".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_verify_args() {
+        let args = verify_args(
+            &Explorer::Blockscout {
+                url: "https://base-sepolia.blockscout.com/api/".to_string(),
+            },
+            84532,
+            &contract(),
+        );
+        assert_eq!(
+            args.join(" "),
+            "verify-contract --chain-id 84532 --verifier blockscout \
+             --verifier-url https://base-sepolia.blockscout.com/api/ --constructor-args 0x01 \
+             0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0 tmp/Contract.sol:Contract"
+        );
+
+        let mut contract = contract();
+        contract.constructor_args = None;
+        let args = verify_args(&Explorer::Sourcify { url: None }, 1, &contract);
+        assert_eq!(
+            args.join(" "),
+            "verify-contract --chain-id 1 --verifier sourcify \
+             0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0 tmp/Contract.sol:Contract"
+        );
+    }
+
+    #[test]
+    fn test_verification_config() {
+        let config: VerificationConfig = serde_json::from_value(serde_json::json!({
+            "explorers": [
+                { "type": "etherscan", "apiKey": "key" },
+                { "type": "sourcify" },
+            ],
+            "initialDelaySecs": 5,
+            "fatal": false,
+        }))
+        .unwrap();
+        assert_eq!(
+            config.explorers[0],
+            Explorer::Etherscan {
+                api_key: Some("key".to_string()),
+                url: None,
+            }
+        );
+        assert_eq!(config.max_attempts, 3);
+        assert!(!config.fatal);
+
+        assert_eq!(config.backoff_delay(1), Duration::from_secs(5));
+        assert_eq!(config.backoff_delay(2), Duration::from_secs(10));
+        assert_eq!(config.backoff_delay(5), Duration::from_secs(60));
+        assert_eq!(config.backoff_delay(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_check_verifications() {
+        let verification = |verified: bool| VerificationResult {
+            contract: "Contract".to_string(),
+            explorer: "etherscan",
+            verified,
+            attempts: 3,
+            error: (!verified).then(|| "timeout".to_string()),
+        };
+        let deployments = vec![
+            DeploymentResult {
+                chain_id: 8453,
+                contracts: vec![],
+                verifications: vec![verification(true)],
+            },
+            DeploymentResult {
+                chain_id: 84532,
+                contracts: vec![],
+                verifications: vec![verification(false)],
+            },
+        ];
+
+        let mut config = VerificationConfig::default();
+        assert!(check_verifications(&config, &deployments[..1]).is_ok());
+        let error = check_verifications(&config, &deployments).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Contract on etherscan (chain 84532"));
+
+        config.fatal = false;
+        assert!(check_verifications(&config, &deployments).is_ok());
+    }
+}
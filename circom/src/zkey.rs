@@ -0,0 +1,107 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::payload::UploadUrls;
+
+/// Describes the artifacts produced by a build that clients need to know about
+/// up front, such as how many chunks the client-side zkey was split into.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub zkey_chunks: Vec<String>,
+}
+
+/// Finds the chunk files snarkjs wrote next to `base_name` in `dir` and returns
+/// their suffixes (`b`, `c`, ..., `z`, `aa`, ...) in the order snarkjs emits them.
+pub fn discover_zkey_chunks(dir: &str, base_name: &str) -> Result<Vec<String>> {
+    let mut chunks = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        let file_name = entry.file_name();
+        let Some(suffix) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(base_name))
+        else {
+            continue;
+        };
+
+        if !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_lowercase()) {
+            chunks.push(suffix.to_string());
+        }
+    }
+
+    chunks.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+    Ok(chunks)
+}
+
+/// Ensures every discovered chunk has an upload URL, so a build never silently drops part of a zkey.
+pub fn check_chunk_destinations(upload_urls: &UploadUrls, chunks: &[String]) -> Result<()> {
+    let missing: Vec<&str> = chunks
+        .iter()
+        .filter(|chunk| !upload_urls.zkey_chunks.contains_key(chunk.as_str()))
+        .map(|chunk| chunk.as_str())
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "No upload URL provided for zkey chunk(s): {}",
+            missing.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Writes the build manifest to `manifest.json` inside `dir`.
+pub fn write_manifest(dir: &str, chunks: &[String]) -> Result<()> {
+    let manifest = Manifest {
+        zkey_chunks: chunks.to_vec(),
+    };
+    fs::write(
+        Path::new(dir).join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_zkey_chunks_orders_by_snarkjs_suffix() {
+        let dir =
+            std::env::temp_dir().join(format!("sdk-circom-zkey-chunks-{}", std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+
+        for name in [
+            "circuit.zkey",
+            "circuit.zkeyc",
+            "circuit.zkeyaa",
+            "circuit.zkeyb",
+            "circuit.zkeyz",
+            "circuit.zkeyb.gz",
+            "circuit_full.zkey",
+        ] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let chunks = discover_zkey_chunks(dir.to_str().unwrap(), "circuit.zkey").unwrap();
+
+        assert_eq!(chunks, vec!["b", "c", "z", "aa"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
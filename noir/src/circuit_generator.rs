@@ -1,13 +1,40 @@
 use crate::models::CircuitTemplateInputs;
 use anyhow::Result;
 use regex::Regex;
+use sdk_utils::{InputGenerationOptions, PublicSignalLayout, load_template};
+use std::{fs, path::Path};
 use tera::{Context, Tera};
 
+pub const CIRCUIT_TEMPLATE_FILE: &str = "template.nr.tera";
+/// Circuit template compiled into the binary, overridable through `$TEMPLATES_DIR`.
+pub const CIRCUIT_TEMPLATE: &str = include_str!("../templates/template.nr.tera");
+
+/// Writes the options needed to generate circuit inputs to `input_options.json` in `out_dir`.
+pub fn write_input_options_json(options: &InputGenerationOptions, out_dir: &Path) -> Result<()> {
+    fs::write(
+        out_dir.join("input_options.json"),
+        serde_json::to_string_pretty(options)?,
+    )?;
+    Ok(())
+}
+
+/// Writes the public signal layout to `signals.json` in `out_dir`.
+pub fn write_signals_json(layout: &PublicSignalLayout, out_dir: &Path) -> Result<()> {
+    fs::write(
+        out_dir.join("signals.json"),
+        serde_json::to_string_pretty(layout)?,
+    )?;
+    Ok(())
+}
+
 /// Generates a Noir circuit file by rendering a Tera template with the provided inputs.
 /// After rendering, consecutive newlines are collapsed into a single newline.
 pub fn generate_circuit(circuit_template_input: CircuitTemplateInputs) -> Result<String> {
     let mut tera = Tera::default();
-    tera.add_template_file("./templates/template.nr.tera", Some("template.nr.tera"))?;
+    tera.add_raw_template(
+        "template.nr.tera",
+        &load_template(CIRCUIT_TEMPLATE_FILE, CIRCUIT_TEMPLATE)?,
+    )?;
 
     let mut context: Context = Context::new();
     context.insert("circuit_name", &circuit_template_input.circuit_name);
@@ -27,6 +54,19 @@ pub fn generate_circuit(circuit_template_input: CircuitTemplateInputs) -> Result
         "remove_soft_linebreaks",
         &circuit_template_input.remove_soft_linebreaks,
     );
+    context.insert(
+        "enable_header_masking",
+        &circuit_template_input.enable_header_masking,
+    );
+    context.insert(
+        "enable_body_masking",
+        &circuit_template_input.enable_body_masking,
+    );
+    context.insert("key_limbs", &circuit_template_input.key_limbs);
+    context.insert(
+        "sha_precompute_selector",
+        &circuit_template_input.sha_precompute_selector,
+    );
     context.insert("regexes", &circuit_template_input.regexes);
     context.insert("external_inputs", &circuit_template_input.external_inputs);
     context.insert("output_signals", &circuit_template_input.output_signals);
@@ -38,3 +78,247 @@ pub fn generate_circuit(circuit_template_input: CircuitTemplateInputs) -> Result
     let re = Regex::new(r"\n+")?;
     Ok(re.replace_all(&circuit, "\n").to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk_utils::{
+        SUPPORTED_RSA_KEY_BITS, SignalEncoding, SignalKind, fixtures,
+        proto_types::proto_blueprint::{
+            Blueprint, ExternalInput, ExternalInputType, HashFunction, ZkFramework,
+        },
+    };
+
+    fn x_export_data_masked() -> Blueprint {
+        let mut blueprint = fixtures::x_export_data(ZkFramework::Noir);
+        blueprint.enable_header_masking = true;
+        blueprint.enable_body_masking = true;
+        blueprint
+    }
+
+    /// Compares the rendered `main.nr` with `snapshots/<name>.nr`.
+    fn assert_circuit_snapshot(name: &str, blueprint: Blueprint) {
+        let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("snapshots")
+            .join(format!("{}.nr", name));
+        fixtures::assert_snapshot(&path, &circuit);
+    }
+
+    /// Splits on commas that are not nested inside brackets or generics.
+    fn split_top_level(list: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut depth = 0;
+        let mut current = String::new();
+        for c in list.chars() {
+            match c {
+                '(' | '[' | '<' => depth += 1,
+                ')' | ']' | '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    items.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        if !current.trim().is_empty() {
+            items.push(current.trim().to_string());
+        }
+        items
+    }
+
+    /// Checks that the declared return types of `main` line up with the returned tuple
+    /// and with `CircuitTemplateInputs::public_outputs`.
+    fn assert_return_tuple_in_sync(blueprint: Blueprint) {
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        let public_outputs = inputs.public_outputs();
+        assert_eq!(inputs.layout().signals.len(), public_outputs.len());
+        let circuit = generate_circuit(inputs).unwrap();
+
+        let signature_start = circuit.find(") -> pub (").unwrap() + ") -> pub (".len();
+        let signature_end = signature_start + circuit[signature_start..].find(") {").unwrap();
+        let return_types = split_top_level(&circuit[signature_start..signature_end]);
+
+        let returned = circuit
+            .lines()
+            .map(str::trim)
+            .rfind(|line| line.starts_with("(pubkey_hash"))
+            .unwrap();
+        let returned_values = split_top_level(&returned[1..returned.len() - 1]);
+
+        assert_eq!(return_types.len(), returned_values.len());
+        assert_eq!(
+            public_outputs
+                .iter()
+                .map(|(_, ty)| ty.clone())
+                .collect::<Vec<_>>(),
+            return_types
+        );
+        assert_eq!(
+            public_outputs
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>(),
+            returned_values
+        );
+    }
+
+    #[test]
+    fn test_snapshot_x_export_data() {
+        assert_circuit_snapshot("x_export_data", fixtures::x_export_data(ZkFramework::Noir));
+    }
+
+    #[test]
+    fn test_snapshot_x_export_data_masked() {
+        assert_circuit_snapshot("x_export_data_masked", x_export_data_masked());
+    }
+
+    #[test]
+    fn test_snapshot_apple_kyc() {
+        assert_circuit_snapshot("apple_kyc", fixtures::apple_kyc(ZkFramework::Noir));
+    }
+
+    #[test]
+    fn test_snapshot_succinct_residency() {
+        assert_circuit_snapshot(
+            "succinct_residency",
+            fixtures::succinct_residency(ZkFramework::Noir),
+        );
+    }
+
+    #[test]
+    fn test_snapshot_kraken_intermediate() {
+        assert_circuit_snapshot(
+            "kraken_intermediate",
+            fixtures::kraken_intermediate(ZkFramework::Noir),
+        );
+    }
+
+    #[test]
+    fn test_snapshot_subject_extract() {
+        assert_circuit_snapshot(
+            "subject_extract",
+            fixtures::subject_extract(ZkFramework::Noir),
+        );
+    }
+
+    #[test]
+    fn test_rsa_key_size() {
+        for bits in SUPPORTED_RSA_KEY_BITS {
+            let mut blueprint = fixtures::subject_extract(ZkFramework::Noir);
+            blueprint.rsa_key_bits = Some(bits);
+            let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+
+            assert!(circuit.contains(&format!("pubkey: RSAPubkey<KEY_LIMBS_{}>,", bits)));
+            assert!(circuit.contains(&format!("signature: [Field; KEY_LIMBS_{}],", bits)));
+        }
+    }
+
+    #[test]
+    fn test_sha_precompute_selector() {
+        let mut blueprint = fixtures::x_export_data(ZkFramework::Noir);
+        blueprint.sha_precompute_selector = "<div id=\"content\">".to_string();
+
+        let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+        assert!(circuit.contains(
+            "partial_body_hash: [u32; 8], // SHA-256 state of the body up to the selector \"<div id=\"content\">\""
+        ));
+    }
+
+    #[test]
+    fn test_salted_poseidon_regex_hash() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Noir);
+        blueprint.decomposed_regexes[0].hash_function = Some(HashFunction::Poseidon as i32);
+        blueprint.decomposed_regexes[0].is_hash_salted = Some(true);
+
+        let inputs = CircuitTemplateInputs::from(blueprint);
+        let hash = inputs
+            .layout()
+            .signal("email_subject_packed_hash")
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            hash.kind,
+            SignalKind::RegexHash {
+                regex: "email_subject".to_string(),
+                salted: true,
+            }
+        );
+        assert_eq!(hash.encoding, SignalEncoding::PoseidonHash);
+
+        let circuit = generate_circuit(inputs).unwrap();
+        assert!(circuit.contains("    email_subject_hash_salt: Field,\n"));
+        assert!(circuit.contains(
+            "let email_subject_packed_hash = poseidon::bn254::hash_7([email_subject_capture_1_packed[0], "
+        ));
+        assert!(circuit.contains("email_subject_capture_2_packed[2], email_subject_hash_salt]);"));
+    }
+
+    #[test]
+    fn test_committed_external_input() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Noir);
+        blueprint.external_inputs[0].is_committed = Some(true);
+        assert_return_tuple_in_sync(blueprint.clone());
+
+        let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+        assert!(circuit.contains("    test: [Field; 133],\n    test_salt: Field,\n"));
+        // 133 packed fields and the salt hashed in chunks of 16, as PoseidonModular does
+        assert!(circuit.contains("let test_commitment = poseidon::bn254::hash_2(["));
+        assert_eq!(
+            circuit.matches("poseidon::bn254::hash_16([test[").count(),
+            8
+        );
+        assert!(circuit.contains("poseidon::bn254::hash_6([test[128], test[129], test[130], test[131], test[132], test_salt])]);"));
+        assert!(circuit.contains(", prover_address, test_commitment, email_subject_packed_hash)"));
+    }
+
+    #[test]
+    fn test_typed_external_inputs() {
+        let mut blueprint = fixtures::kraken_intermediate(ZkFramework::Noir);
+        for (name, input_type) in [
+            ("recipient", ExternalInputType::Address),
+            ("amount", ExternalInputType::Uint256),
+            ("digest", ExternalInputType::Bytes32),
+            ("accepted", ExternalInputType::Bool),
+        ] {
+            blueprint.external_inputs.push(ExternalInput {
+                name: name.to_string(),
+                input_type: Some(input_type as i32),
+                is_committed: Some(name == "amount"),
+                ..Default::default()
+            });
+        }
+        assert_return_tuple_in_sync(blueprint.clone());
+
+        let circuit = generate_circuit(CircuitTemplateInputs::from(blueprint)).unwrap();
+        assert!(circuit.contains("    recipient: Field,\n"));
+        assert!(circuit.contains("    digest: [Field; 2],\n"));
+        assert!(circuit.contains("recipient.assert_max_bit_size::<160>();"));
+        assert!(circuit.contains("digest[1].assert_max_bit_size::<128>();"));
+        assert!(circuit.contains("assert(accepted * (accepted - 1) == 0);"));
+        assert!(
+            circuit.contains(
+                "let amount_commitment = poseidon::bn254::hash_2([amount, amount_salt]);"
+            )
+        );
+        assert!(circuit.contains(
+            ", prover_address, test, recipient, amount_commitment, digest, accepted, email_subject_packed_hash)"
+        ));
+    }
+
+    #[test]
+    fn test_return_tuple_in_sync() {
+        for blueprint in [
+            fixtures::x_export_data(ZkFramework::Noir),
+            x_export_data_masked(),
+            fixtures::apple_kyc(ZkFramework::Noir),
+            fixtures::succinct_residency(ZkFramework::Noir),
+            fixtures::kraken_intermediate(ZkFramework::Noir),
+            fixtures::subject_extract(ZkFramework::Noir),
+        ] {
+            assert_return_tuple_in_sync(blueprint);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use relayer_utils::LOG;
+use sdk_utils::{proto_types::proto_blueprint::Blueprint, validate_blueprint};
+use slog::info;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    circuit_generator::{generate_circuit, write_input_options_json, write_signals_json},
+    handlers::Payload,
+    models::CircuitTemplateInputs,
+    regex_generator::generate_regex_circuits,
+};
+
+/// Options for rendering a blueprint without compiling it,
+/// passed as `--dry-run <out_dir> [--blueprint <file.json>]`.
+pub struct DryRunOptions {
+    pub out_dir: PathBuf,
+    pub blueprint_path: Option<PathBuf>,
+}
+
+impl DryRunOptions {
+    /// Parses the dry-run flags from the command line, returning `None` when serving requests.
+    pub fn from_args(args: &[String]) -> Result<Option<Self>> {
+        let Some(position) = args.iter().position(|arg| arg == "--dry-run") else {
+            return Ok(None);
+        };
+
+        let out_dir = args
+            .get(position + 1)
+            .filter(|arg| !arg.starts_with("--"))
+            .ok_or_else(|| anyhow!("--dry-run requires an output directory"))?;
+
+        let blueprint_path = match args.iter().position(|arg| arg == "--blueprint") {
+            Some(position) => {
+                Some(PathBuf::from(args.get(position + 1).ok_or_else(|| {
+                    anyhow!("--blueprint requires a path to a blueprint JSON")
+                })?))
+            }
+            None => None,
+        };
+
+        Ok(Some(DryRunOptions {
+            out_dir: PathBuf::from(out_dir),
+            blueprint_path,
+        }))
+    }
+
+    /// Loads the blueprint from `--blueprint` if given, otherwise from the base64 `PAYLOAD` env var.
+    pub fn load_blueprint(&self) -> Result<Blueprint> {
+        match &self.blueprint_path {
+            Some(path) => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            None => {
+                let encoded = std::env::var("PAYLOAD")
+                    .map_err(|_| anyhow!("PAYLOAD environment variable not set"))?;
+                let decoded = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+                let payload: Payload = serde_json::from_slice(&decoded)?;
+                Ok(payload.blueprint)
+            }
+        }
+    }
+}
+
+/// Validates the blueprint and renders the regex circuits and `main.nr` into a Nargo
+/// project at `out_dir`, then prints the public outputs of the circuit.
+pub fn dry_run(blueprint: Blueprint, out_dir: &Path) -> Result<()> {
+    info!(LOG, "Dry run"; "out_dir" => out_dir.display().to_string());
+
+    validate_blueprint(&blueprint)?;
+
+    fs::create_dir_all(out_dir.join("src"))?;
+    fs::copy("./Nargo.toml.txt", out_dir.join("Nargo.toml"))?;
+    generate_regex_circuits(&blueprint.decomposed_regexes, out_dir)?;
+
+    let circuit_name = blueprint.circuit_name.clone();
+    let circuit_template_inputs = CircuitTemplateInputs::from(blueprint);
+    let public_outputs = circuit_template_inputs.public_outputs();
+    let layout = circuit_template_inputs.layout();
+    let input_options = circuit_template_inputs.plan.input_generation_options();
+    let regexes: Vec<String> = circuit_template_inputs
+        .regexes
+        .iter()
+        .map(|regex| {
+            format!(
+                "  {} ({}, max match length {}, {} public part(s){})",
+                regex.name,
+                regex.location,
+                regex.max_match_length,
+                regex.num_public_parts,
+                if regex.is_hashed { ", hashed" } else { "" }
+            )
+        })
+        .collect();
+
+    let circuit = generate_circuit(circuit_template_inputs)?;
+    fs::write(out_dir.join("src").join("main.nr"), circuit)?;
+    write_signals_json(&layout, out_dir)?;
+    write_input_options_json(&input_options, out_dir)?;
+
+    println!("Rendered {} into {}", circuit_name, out_dir.display());
+
+    println!("\nRegexes:");
+    for regex in regexes {
+        println!("{}", regex);
+    }
+
+    println!("\nPublic outputs:");
+    for (i, (name, ty)) in public_outputs.iter().enumerate() {
+        println!("  [{}] {}: {}", i, name, ty);
+    }
+
+    println!("\nPublic signals:");
+    for signal in &layout.signals {
+        println!(
+            "  [{}..{}] {}",
+            signal.start_idx,
+            signal.start_idx + signal.width,
+            signal.name
+        );
+    }
+    println!("  total: {}", layout.len());
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use relayer_utils::LOG;
-use sdk_utils::{run_command, upload_to_url};
+use sdk_utils::{run_command, upload_to_url, zip_dir_filtered, zip_paths};
 use slog::info;
 use std::{fs, path::Path};
 
@@ -28,6 +28,12 @@ impl FileUploader for ProductionFileUploader {
             "application/zip",
         )
         .await?;
+        if let Some(url) = &upload_urls.signals {
+            upload_to_url(url, "./tmp/signals.json", "application/json").await?;
+        }
+        if let Some(url) = &upload_urls.input_options {
+            upload_to_url(url, "./tmp/input_options.json", "application/json").await?;
+        }
 
         Ok(())
     }
@@ -83,21 +89,19 @@ pub async fn compile_circuit() -> Result<()> {
 pub async fn cleanup() -> Result<()> {
     info!(LOG, "Cleaning up");
 
+    let tmp_path = Path::new("tmp");
+
     info!(LOG, "Zipping circuit");
-    run_command(
-        "zip",
-        &["-r", "circuit.zip", "src", "Nargo.toml"],
-        Some("tmp"),
-    )
-    .await?;
+    zip_paths(
+        tmp_path,
+        &["src", "Nargo.toml"],
+        tmp_path.join("circuit.zip"),
+    )?;
 
     info!(LOG, "Zipping regex graphs");
-    run_command(
-        "zip",
-        &["-r", "regex_graphs.zip", ".", "-i", "*_regex.json"],
-        Some("tmp"),
-    )
-    .await?;
+    zip_dir_filtered(tmp_path, tmp_path.join("regex_graphs.zip"), |path| {
+        path.to_string_lossy().ends_with("_regex.json")
+    })?;
 
     Ok(())
 }
@@ -1,5 +1,6 @@
 pub mod circuit_generator;
 pub mod db;
+pub mod dry_run;
 pub mod filesystem;
 pub mod handlers;
 pub mod models;
@@ -4,6 +4,7 @@ use dotenv::dotenv;
 use relayer_utils::LOG;
 use slog::warn;
 
+use noir::dry_run::{DryRunOptions, dry_run};
 use noir::handlers::compile_circuit_handler;
 
 #[derive(Clone)]
@@ -15,6 +16,11 @@ struct AppState {
 async fn main() -> Result<()> {
     dotenv().ok();
 
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(options) = DryRunOptions::from_args(&args)? {
+        return dry_run(options.load_blueprint()?, &options.out_dir);
+    }
+
     let state = AppState {
         api_key: std::env::var("ZKEMAIL_API_KEY").expect("ZKEMAIL_API_KEY must be set"),
     };
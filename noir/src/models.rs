@@ -1,5 +1,8 @@
-use sdk_utils::compute_signal_length;
 use sdk_utils::proto_types::proto_blueprint::Blueprint;
+use sdk_utils::{
+    CircuitPlan, ExternalInputType, HashFunction, PublicSignal, PublicSignalLayout, RegexLocation,
+    SignalEncoding, SignalKind,
+};
 use serde::Serialize;
 
 /// Represents a single decomposed regex, along with computed fields
@@ -16,6 +19,10 @@ pub struct RegexEntry {
     pub is_hashed: bool,
     pub hash_packing_size: usize,
     pub hash_inputs: String,
+    /// Whether the hash takes a private `<name>_hash_salt` after the packed parts.
+    pub is_hash_salted: bool,
+    /// Expression computing the packed hash from `hash_inputs`.
+    pub hash_expression: String,
     pub capture_string: String,
 }
 
@@ -23,8 +30,15 @@ pub struct RegexEntry {
 #[derive(Serialize)]
 pub struct ExternalInputEntry {
     pub name: String,
+    pub input_type: ExternalInputType,
+    /// Type of the parameter, `Field` for single field inputs or `[Field; N]`.
+    pub noir_type: String,
     pub max_length: usize,
     pub signal_length: usize,
+    /// Whether the input is private and `main` returns `<name>_commitment` instead.
+    pub is_committed: bool,
+    /// Expression computing the Poseidon commitment to the input and `<name>_salt`.
+    pub commitment_expression: String,
 }
 
 /// A struct that holds all the data required to render the circuit template.
@@ -35,136 +49,205 @@ pub struct CircuitTemplateInputs {
     pub email_body_max_length: usize,
     pub ignore_body_hash_check: bool,
     pub remove_soft_linebreaks: bool,
+    pub enable_header_masking: bool,
+    pub enable_body_masking: bool,
+    /// Name of the zkemail constant holding the number of RSA key limbs, e.g. `KEY_LIMBS_2048`.
+    pub key_limbs: String,
+    pub sha_precompute_selector: Option<String>,
     pub regexes: Vec<RegexEntry>,
     pub external_inputs: Vec<ExternalInputEntry>,
+    /// Noir types of the public return tuple of `main`, derived from the signal layout.
     pub output_args: String,
+    /// Expressions returned by `main`, in the same order as `output_args`.
     pub output_signals: String,
+    #[serde(skip)]
+    pub plan: CircuitPlan,
+}
+
+impl CircuitTemplateInputs {
+    /// Returns the public signals of the compiled circuit.
+    pub fn layout(&self) -> PublicSignalLayout {
+        PublicSignalLayout::noir(&self.plan)
+    }
+
+    /// Returns the elements of the public return tuple of `main` with their Noir types.
+    pub fn public_outputs(&self) -> Vec<(String, String)> {
+        self.layout().signals.iter().map(public_output).collect()
+    }
+}
+
+/// Returns the expression `main` returns for a public signal and its Noir type.
+fn public_output(signal: &PublicSignal) -> (String, String) {
+    let max_length = signal.max_length.unwrap_or_default();
+    let (expression, noir_type) = match &signal.kind {
+        SignalKind::HeaderHashHi => ("header_hash[0]".to_string(), "Field".to_string()),
+        SignalKind::HeaderHashLo => ("header_hash[1]".to_string(), "Field".to_string()),
+        SignalKind::ProverAddress => (signal.name.clone(), "[Field; 1]".to_string()),
+        SignalKind::MaskedHeader | SignalKind::MaskedBody => {
+            (signal.name.clone(), format!("[u8; {}]", max_length))
+        }
+        SignalKind::RegexCapture { .. } => (
+            signal.name.clone(),
+            format!("BoundedVec<u8, {}>", max_length),
+        ),
+        SignalKind::ExternalInput
+            if matches!(
+                signal.encoding,
+                SignalEncoding::PackedBytes | SignalEncoding::Bytes32
+            ) =>
+        {
+            (signal.name.clone(), format!("[Field; {}]", signal.width))
+        }
+        _ => (signal.name.clone(), "Field".to_string()),
+    };
+    (expression, noir_type)
+}
+
+/// Number of inputs `PoseidonModular` hashes per chunk.
+const POSEIDON_CHUNK_SIZE: usize = 16;
+
+/// Builds the Noir expression hashing `inputs`. Poseidon follows `PoseidonModular` of
+/// Circom: chunks of 16 inputs are hashed and chained with `hash_2`, so both frameworks
+/// commit to the same value.
+fn hash_expression(hash_function: HashFunction, inputs: &[String]) -> String {
+    match hash_function {
+        HashFunction::Pedersen => format!("pedersen_hash([{}])", inputs.join(", ")),
+        HashFunction::Poseidon => inputs
+            .chunks(POSEIDON_CHUNK_SIZE)
+            .map(|chunk| {
+                format!(
+                    "poseidon::bn254::hash_{}([{}])",
+                    chunk.len(),
+                    chunk.join(", ")
+                )
+            })
+            .reduce(|acc, chunk| format!("poseidon::bn254::hash_2([{}, {}])", acc, chunk))
+            .unwrap_or_default(),
+    }
 }
 
 impl From<Blueprint> for CircuitTemplateInputs {
     fn from(value: Blueprint) -> Self {
-        let circuit_name = value.circuit_name;
-        let email_header_max_length = value.email_header_max_length as usize;
-        let email_body_max_length = value.email_body_max_length as usize;
-        let ignore_body_hash_check = value.ignore_body_hash_check;
-        let remove_soft_linebreaks = value.remove_soft_linebreaks;
-
-        // Process regexes
-        let mut regexes = Vec::new();
-        for regex in value.decomposed_regexes {
-            let name = regex.name.clone();
-            let max_match_length = regex.max_match_length as usize;
-            let regex_circuit_name = format!("{}_regex", regex.name);
-
-            // Determine location and its max length
-            let (location, max_length_of_location) = if regex.location == "header" {
-                ("header".to_string(), email_header_max_length)
-            } else if remove_soft_linebreaks {
-                ("decoded_body".to_string(), email_body_max_length)
-            } else {
-                ("body".to_string(), email_body_max_length)
-            };
-
-            let mut num_public_parts = 0;
-            let mut public_parts_max_length = Vec::<usize>::with_capacity(num_public_parts);
-            let is_hashed = regex.is_hashed.unwrap_or(false);
-            let mut hash_inputs = Vec::new();
-            let mut capture_string = String::new();
-            let hash_packing_size = ((max_match_length as f64) / 31.0).ceil() as usize;
-
-            for part in &regex.parts {
-                if part.is_public == Some(true) {
-                    num_public_parts += 1;
-                    public_parts_max_length.push(part.max_length() as usize);
-
-                    for i in 0..hash_packing_size {
-                        hash_inputs.push(format!(
-                            "{}_capture_{}_packed[{}]",
-                            name, num_public_parts, i
-                        ));
-                    }
+        CircuitTemplateInputs::from(&CircuitPlan::from(&value))
+    }
+}
 
-                    // Create capture string (e.g., "capture_1, capture_2, ...")
-                    if capture_string.is_empty() {
-                        capture_string.push_str(&format!("{}_capture_{}", name, num_public_parts));
-                    } else {
-                        capture_string
-                            .push_str(&format!(", {}_capture_{}", name, num_public_parts));
+impl From<&CircuitPlan> for CircuitTemplateInputs {
+    fn from(plan: &CircuitPlan) -> Self {
+        let regexes: Vec<RegexEntry> = plan
+            .regexes
+            .iter()
+            .map(|regex| {
+                let name = regex.name.clone();
+
+                // Map the location to the variable holding it
+                let location = match regex.location {
+                    RegexLocation::Header => "header",
+                    RegexLocation::DecodedBody => "decoded_body",
+                    RegexLocation::Body => "body",
+                };
+
+                let mut hash_inputs = Vec::new();
+                let mut captures = Vec::new();
+                for i in 1..=regex.captures.len() {
+                    for j in 0..regex.match_packed_width {
+                        hash_inputs.push(format!("{}_capture_{}_packed[{}]", name, i, j));
                     }
+                    // Create capture string (e.g., "capture_1, capture_2, ...")
+                    captures.push(format!("{}_capture_{}", name, i));
+                }
+                let is_hash_salted = regex.is_hashed && regex.is_hash_salted;
+                if is_hash_salted {
+                    hash_inputs.push(format!("{}_hash_salt", name));
                 }
-            }
-
-            regexes.push(RegexEntry {
-                name,
-                max_match_length,
-                regex_circuit_name,
-                location,
-                max_length_of_location,
-                num_public_parts,
-                public_parts_max_length,
-                is_hashed,
-                hash_packing_size,
-                hash_inputs: if is_hashed {
-                    hash_inputs.join(", ")
+                let hash_expression = if regex.is_hashed {
+                    hash_expression(regex.hash_function_or(HashFunction::Pedersen), &hash_inputs)
                 } else {
                     String::new()
-                },
-                capture_string,
-            });
-        }
+                };
+
+                RegexEntry {
+                    regex_circuit_name: format!("{}_regex", name),
+                    name,
+                    max_match_length: regex.max_match_length,
+                    location: location.to_string(),
+                    max_length_of_location: regex.max_length_of_location,
+                    num_public_parts: regex.captures.len(),
+                    public_parts_max_length: regex
+                        .captures
+                        .iter()
+                        .map(|capture| capture.max_length)
+                        .collect(),
+                    is_hashed: regex.is_hashed,
+                    hash_packing_size: regex.match_packed_width,
+                    hash_inputs: if regex.is_hashed {
+                        hash_inputs.join(", ")
+                    } else {
+                        String::new()
+                    },
+                    is_hash_salted,
+                    hash_expression,
+                    capture_string: captures.join(", "),
+                }
+            })
+            .collect();
 
-        // Process external inputs
-        let external_inputs_data = value.external_inputs;
-        let external_inputs: Vec<ExternalInputEntry> = external_inputs_data
+        let external_inputs: Vec<ExternalInputEntry> = plan
+            .external_inputs
             .iter()
             .map(|input| {
-                let signal_length = compute_signal_length(input.max_length as usize);
+                let is_array = input.input_type.is_array();
+                let commitment_expression = if input.is_committed {
+                    let mut commitment_inputs: Vec<String> = if is_array {
+                        (0..input.packed_width)
+                            .map(|i| format!("{}[{}]", input.name, i))
+                            .collect()
+                    } else {
+                        vec![input.name.clone()]
+                    };
+                    commitment_inputs.push(format!("{}_salt", input.name));
+                    hash_expression(HashFunction::Poseidon, &commitment_inputs)
+                } else {
+                    String::new()
+                };
                 ExternalInputEntry {
                     name: input.name.clone(),
-                    max_length: input.max_length as usize,
-                    signal_length,
+                    input_type: input.input_type,
+                    noir_type: if is_array {
+                        format!("[Field; {}]", input.packed_width)
+                    } else {
+                        "Field".to_string()
+                    },
+                    max_length: input.max_length,
+                    signal_length: input.packed_width,
+                    is_committed: input.is_committed,
+                    commitment_expression,
                 }
             })
             .collect();
 
-        // Compute output signals and args
-        let mut output_signals = String::new();
-        let mut output_args = String::new();
-        for input in &external_inputs {
-            output_signals.push_str(&format!(", {}", input.name));
-            output_args.push_str(&format!(", [Field; {}]", input.signal_length));
-        }
-        for regex in &regexes {
-            if regex.num_public_parts > 0 {
-                if regex.is_hashed {
-                    output_signals.push_str(&format!(", {}_packed_hash", regex.name));
-                } else {
-                    output_signals.push_str(&format!(", {}", regex.capture_string));
-                }
-                for i in 0..regex.num_public_parts {
-                    if regex.is_hashed {
-                        output_args.push_str(", Field");
-                        break;
-                    } else {
-                        output_args.push_str(&format!(
-                            ", BoundedVec<u8, {}>",
-                            regex.public_parts_max_length[i]
-                        ));
-                    }
-                }
-            }
-        }
+        let (output_signals, output_args): (Vec<String>, Vec<String>) =
+            PublicSignalLayout::noir(&plan)
+                .signals
+                .iter()
+                .map(public_output)
+                .unzip();
 
         CircuitTemplateInputs {
-            circuit_name,
-            email_header_max_length,
-            email_body_max_length,
-            ignore_body_hash_check,
-            remove_soft_linebreaks,
+            circuit_name: plan.circuit_name.clone(),
+            email_header_max_length: plan.email_header_max_length,
+            email_body_max_length: plan.email_body_max_length,
+            ignore_body_hash_check: plan.ignore_body_hash_check,
+            remove_soft_linebreaks: plan.remove_soft_linebreaks,
+            enable_header_masking: plan.enable_header_masking,
+            enable_body_masking: plan.masks_body(),
+            key_limbs: format!("KEY_LIMBS_{}", plan.rsa_key_bits),
+            sha_precompute_selector: plan.sha_precompute_selector.clone(),
             regexes,
             external_inputs,
-            output_args,
-            output_signals,
+            output_args: output_args.join(", "),
+            output_signals: output_signals.join(", "),
+            plan: plan.clone(),
         }
     }
 }
@@ -1,10 +1,13 @@
 use anyhow::{Result, anyhow};
 use sdk_utils::proto_types::proto_blueprint::DecomposedRegex;
-use std::fs;
+use std::{fs, path::Path};
 use zk_regex_compiler::{DecomposedRegexConfig, ProvingFramework, RegexPart, gen_from_decomposed};
 
-/// Generates Noir files for the provided decomposed regexes.
-pub fn generate_regex_circuits(decomposed_regexes: &Vec<DecomposedRegex>) -> Result<()> {
+/// Generates Noir files for the provided decomposed regexes inside the Nargo project at `project_dir`.
+pub fn generate_regex_circuits(
+    decomposed_regexes: &Vec<DecomposedRegex>,
+    project_dir: &Path,
+) -> Result<()> {
     for decomposed_regex in decomposed_regexes {
         let mut decomposed_regex_config = Vec::new();
         for part in decomposed_regex.parts.clone() {
@@ -29,9 +32,11 @@ pub fn generate_regex_circuits(decomposed_regexes: &Vec<DecomposedRegex>) -> Res
 
         let (graph, code) =
             gen_from_decomposed(config, &decomposed_regex.name, ProvingFramework::Noir)?;
-        let file_path = format!("./tmp/src/{}_regex.nr", decomposed_regex.name);
+        let file_path = project_dir
+            .join("src")
+            .join(format!("{}_regex.nr", decomposed_regex.name));
         fs::write(file_path, code)?;
-        let graph_path = format!("./tmp/{}_regex.json", decomposed_regex.name);
+        let graph_path = project_dir.join(format!("{}_regex.json", decomposed_regex.name));
         fs::write(graph_path, serde_json::to_string(&graph)?)?;
     }
     Ok(())
@@ -11,6 +11,11 @@ fn main() {
     let mut prost_build = prost_build::Config::new();
     prost_build
         .type_attribute(".", "#[derive(serde::Serialize,serde::Deserialize)]")
+        // Repeated fields added after the first release may be missing from stored blueprints
+        .field_attribute(
+            ".blueprint.Blueprint.additional_sender_domains",
+            "#[serde(default)]",
+        )
         .extern_path(".google.protobuf.Any", "::prost_wkt_types::Any")
         .extern_path(".google.protobuf.Timestamp", "::prost_wkt_types::Timestamp")
         .extern_path(".google.protobuf.Value", "::prost_wkt_types::Value")
@@ -62,6 +62,8 @@ impl<'de> Deserialize<'de> for Status {
 pub struct ExternalInput {
     pub name: String,
     pub max_length: usize,
+    pub is_committed: Option<bool>,
+    pub input_type: Option<usize>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -77,6 +79,8 @@ pub struct DecomposedRegex {
     pub max_length: usize,
     pub location: String,
     pub is_hashed: Option<bool>,
+    pub hash_function: Option<usize>,
+    pub is_hash_salted: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -98,9 +102,13 @@ pub struct Blueprint {
     pub ignore_body_hash_check: Option<bool>,
     pub remove_soft_line_breaks: Option<bool>,
     pub sha_precompute_selector: Option<String>,
+    pub rsa_key_bits: Option<usize>,
+    pub enable_nullifier: Option<bool>,
+    pub enforce_unique_nullifier: Option<bool>,
     pub email_header_max_length: Option<usize>,
     pub email_body_max_length: Option<usize>,
     pub sender_domain: Option<String>,
+    pub additional_sender_domains: Option<Vec<String>>,
     pub enable_header_masking: Option<bool>,
     pub enable_body_masking: Option<bool>,
     pub zk_framework: Option<ZkFramework>,
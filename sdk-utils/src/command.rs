@@ -7,9 +7,27 @@ use std::{
 };
 
 pub async fn run_command(command: &str, args: &[&str], dir: Option<&str>) -> Result<()> {
+    run_command_with_env(command, args, dir, &[]).await
+}
+
+/// Like `run_command`, with environment variables set on the child process only. Variables
+/// without a value are removed from the child's environment.
+pub async fn run_command_with_env(
+    command: &str,
+    args: &[&str],
+    dir: Option<&str>,
+    envs: &[(&str, Option<&str>)],
+) -> Result<()> {
     let mut cmd = Command::new(command);
     cmd.stdin(Stdio::piped()).stdout(Stdio::piped());
 
+    for (key, value) in envs {
+        match value {
+            Some(value) => cmd.env(key, value),
+            None => cmd.env_remove(key),
+        };
+    }
+
     // Set arguments if provided
     if !args.is_empty() {
         cmd.args(args);
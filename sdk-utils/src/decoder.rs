@@ -0,0 +1,188 @@
+use anyhow::{anyhow, Result};
+
+use crate::{PublicSignalLayout, SignalEncoding};
+
+/// Number of bytes packed into a single field element.
+const PACK_SIZE: usize = 31;
+
+/// A public signal decoded back into a readable value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedSignal {
+    pub name: String,
+    pub value: String,
+}
+
+/// Decodes the public signals of a proof into named values following `layout`.
+///
+/// Signals may be given as decimal strings (snarkjs) or `0x` prefixed hex (Noir).
+/// Byte values are returned as UTF-8 strings, hashes, fields and `bytes32` as 32 byte
+/// hex, addresses as 20 byte hex and booleans as `true` or `false`.
+pub fn decode_public_signals(
+    layout: &PublicSignalLayout,
+    public_signals: &[String],
+) -> Result<Vec<DecodedSignal>> {
+    if public_signals.len() != layout.len() {
+        return Err(anyhow!(
+            "Expected {} public signals, got {}",
+            layout.len(),
+            public_signals.len()
+        ));
+    }
+
+    layout
+        .signals
+        .iter()
+        .map(|signal| {
+            let fields = public_signals[signal.start_idx..signal.start_idx + signal.width]
+                .iter()
+                .map(|value| parse_field(value))
+                .collect::<Result<Vec<_>>>()?;
+
+            let value = match signal.encoding {
+                SignalEncoding::Field
+                | SignalEncoding::PoseidonHash
+                | SignalEncoding::PedersenHash => format!("0x{}", hex_encode(&fields[0])),
+                SignalEncoding::Address => format!("0x{}", hex_encode(&fields[0][12..])),
+                SignalEncoding::Bytes32 => format!(
+                    "0x{}{}",
+                    hex_encode(&fields[0][16..]),
+                    hex_encode(&fields[1][16..])
+                ),
+                SignalEncoding::Bool => (fields[0][31] == 1).to_string(),
+                SignalEncoding::Bytes => bytes_to_string(fields.iter().map(|field| field[31])),
+                SignalEncoding::PackedBytes => {
+                    let bytes = fields
+                        .iter()
+                        .flat_map(|field| field[32 - PACK_SIZE..].iter().rev().copied());
+                    bytes_to_string(bytes.take(signal.max_length.unwrap_or(usize::MAX)))
+                }
+                SignalEncoding::BoundedBytes => {
+                    let (len, storage) = fields
+                        .split_last()
+                        .ok_or_else(|| anyhow!("Signal {} is empty", signal.name))?;
+                    let len = u32::from_be_bytes(len[28..].try_into()?) as usize;
+                    bytes_to_string(storage.iter().take(len).map(|field| field[31]))
+                }
+            };
+
+            Ok(DecodedSignal {
+                name: signal.name.clone(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Parses a decimal or `0x` prefixed hex field element into 32 big-endian bytes.
+fn parse_field(value: &str) -> Result<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+
+    if let Some(hex) = value.strip_prefix("0x") {
+        if hex.len() > 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid hex field element '{}'", value));
+        }
+        let padded = format!("{:0>64}", hex);
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&padded[2 * i..2 * i + 2], 16)?;
+        }
+        return Ok(bytes);
+    }
+
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(anyhow!("Invalid decimal field element '{}'", value));
+    }
+    for digit in value.bytes().map(|c| (c - b'0') as u32) {
+        // bytes = bytes * 10 + digit
+        let mut carry = digit;
+        for byte in bytes.iter_mut().rev() {
+            let next = *byte as u32 * 10 + carry;
+            *byte = next as u8;
+            carry = next >> 8;
+        }
+        if carry != 0 {
+            return Err(anyhow!("Field element '{}' exceeds 32 bytes", value));
+        }
+    }
+    Ok(bytes)
+}
+
+fn bytes_to_string(bytes: impl Iterator<Item = u8>) -> String {
+    let mut bytes: Vec<u8> = bytes.collect();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PublicSignal, SignalKind};
+
+    fn signal(
+        name: &str,
+        start_idx: usize,
+        width: usize,
+        encoding: SignalEncoding,
+    ) -> PublicSignal {
+        PublicSignal {
+            name: name.to_string(),
+            start_idx,
+            width,
+            kind: SignalKind::ExternalInput,
+            encoding,
+            max_length: None,
+        }
+    }
+
+    #[test]
+    fn test_decode_public_signals() {
+        let layout = PublicSignalLayout {
+            signals: vec![
+                signal("pubkeyHash", 0, 1, SignalEncoding::Field),
+                signal("subject", 1, 1, SignalEncoding::PackedBytes),
+                signal("address", 2, 1, SignalEncoding::Address),
+                signal("capture", 3, 4, SignalEncoding::BoundedBytes),
+                signal("digest", 7, 2, SignalEncoding::Bytes32),
+                signal("flag", 9, 1, SignalEncoding::Bool),
+            ],
+        };
+
+        // "hello" packed little-endian: 0x6f6c6c6568
+        let public_signals: Vec<String> = [
+            "255",
+            "478560413032",
+            "0x000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            "0x68",
+            "0x69",
+            "0x00",
+            "0x02",
+            "0x000102030405060708090a0b0c0d0e0f",
+            "0x101112131415161718191a1b1c1d1e1f",
+            "1",
+        ]
+        .iter()
+        .map(|value| value.to_string())
+        .collect();
+
+        let decoded = decode_public_signals(&layout, &public_signals).unwrap();
+        let values: Vec<&str> = decoded.iter().map(|d| d.value.as_str()).collect();
+        assert_eq!(
+            values,
+            vec![
+                "0x00000000000000000000000000000000000000000000000000000000000000ff",
+                "hello",
+                "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                "hi",
+                "0x000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "true",
+            ]
+        );
+
+        assert!(decode_public_signals(&layout, &public_signals[1..]).is_err());
+    }
+}
@@ -0,0 +1,541 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{CircuitPlan, ExternalInputPlan, ExternalInputType, HashFunction, RegexOutput};
+
+/// What a public signal carries.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SignalKind {
+    MaskedBody,
+    MaskedHeader,
+    PubkeyHash,
+    /// Poseidon hash of the DKIM signature. Only comparable between proofs of the same
+    /// framework, see `NullifierFramework`.
+    EmailNullifier {
+        framework: NullifierFramework,
+    },
+    HeaderHashHi,
+    HeaderHashLo,
+    RegexCapture {
+        regex: String,
+        index: usize,
+    },
+    RegexHash {
+        regex: String,
+        salted: bool,
+    },
+    ProverAddress,
+    ExternalInput,
+    /// Poseidon commitment to a private external input and its salt.
+    ExternalInputCommitment {
+        input: String,
+    },
+}
+
+/// Circuit framework that computed an email nullifier. Circom hashes the signature in 17 limbs
+/// of 121 bits and Noir in 18 limbs of 120 bits, so the same email gets a different
+/// nullifier in each framework.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NullifierFramework {
+    Circom,
+    Noir,
+}
+
+/// How the field elements of a public signal encode its value.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SignalEncoding {
+    /// A single field element.
+    Field,
+    /// An Ethereum address in the low 20 bytes of a field element.
+    Address,
+    /// One byte per field element.
+    Bytes,
+    /// Bytes packed little-endian, 31 per field element.
+    PackedBytes,
+    /// One byte per field element followed by the length, as a Noir `BoundedVec<u8, N>`.
+    BoundedBytes,
+    /// Poseidon hash of packed bytes.
+    PoseidonHash,
+    /// Pedersen hash of packed bytes.
+    PedersenHash,
+    /// 32 bytes split into a high and a low 16 byte field element.
+    Bytes32,
+    /// A field element that is 0 or 1.
+    Bool,
+}
+
+impl From<ExternalInputType> for SignalEncoding {
+    fn from(input_type: ExternalInputType) -> Self {
+        match input_type {
+            ExternalInputType::String => SignalEncoding::PackedBytes,
+            ExternalInputType::Address => SignalEncoding::Address,
+            ExternalInputType::Uint256 => SignalEncoding::Field,
+            ExternalInputType::Bytes32 => SignalEncoding::Bytes32,
+            ExternalInputType::Bool => SignalEncoding::Bool,
+        }
+    }
+}
+
+impl From<HashFunction> for SignalEncoding {
+    fn from(hash_function: HashFunction) -> Self {
+        match hash_function {
+            HashFunction::Poseidon => SignalEncoding::PoseidonHash,
+            HashFunction::Pedersen => SignalEncoding::PedersenHash,
+        }
+    }
+}
+
+/// A named public signal occupying `width` consecutive entries starting at `start_idx`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicSignal {
+    pub name: String,
+    pub start_idx: usize,
+    pub width: usize,
+    #[serde(flatten)]
+    pub kind: SignalKind,
+    pub encoding: SignalEncoding,
+    /// Maximum length in bytes of the value before packing or hashing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+}
+
+/// Order and width of the public signals of a compiled circuit, shared by the circuit
+/// template, the Solidity contract and the exported `signals.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublicSignalLayout {
+    pub signals: Vec<PublicSignal>,
+}
+
+impl PublicSignalLayout {
+    /// Builds the layout of a Circom circuit. Circom exposes the outputs of the main
+    /// template in declaration order, followed by the public inputs.
+    pub fn circom(plan: &CircuitPlan) -> Self {
+        let mut layout = PublicSignalLayout::default();
+
+        if plan.masks_body() {
+            layout.push(
+                "maskedBody",
+                plan.email_body_max_length,
+                SignalKind::MaskedBody,
+                SignalEncoding::Bytes,
+                Some(plan.email_body_max_length),
+            );
+        }
+        if plan.enable_header_masking {
+            layout.push(
+                "maskedHeader",
+                plan.email_header_max_length,
+                SignalKind::MaskedHeader,
+                SignalEncoding::Bytes,
+                Some(plan.email_header_max_length),
+            );
+        }
+        layout.push_field("pubkeyHash", SignalKind::PubkeyHash);
+        layout.push_field("headerHashHi", SignalKind::HeaderHashHi);
+        layout.push_field("headerHashLo", SignalKind::HeaderHashLo);
+        if plan.enable_nullifier {
+            layout.push_field(
+                "emailNullifier",
+                SignalKind::EmailNullifier {
+                    framework: NullifierFramework::Circom,
+                },
+            );
+        }
+
+        for output in plan.regex_outputs() {
+            match output {
+                RegexOutput::Hash { regex } => layout.push_regex_hash(
+                    plan,
+                    &format!("{}PackedHash", regex),
+                    regex,
+                    HashFunction::Poseidon,
+                ),
+                RegexOutput::Capture {
+                    regex,
+                    index,
+                    max_length,
+                    packed_width,
+                } => {
+                    let name = if index == 0 {
+                        format!("{}PackedOut", regex)
+                    } else {
+                        format!("{}PackedOut{}", regex, index)
+                    };
+                    layout.push(
+                        &name,
+                        packed_width,
+                        SignalKind::RegexCapture { regex, index },
+                        SignalEncoding::PackedBytes,
+                        Some(max_length),
+                    );
+                }
+            }
+        }
+
+        // Commitments are declared as outputs after the regexes
+        for input in plan
+            .external_inputs
+            .iter()
+            .filter(|input| input.is_committed)
+        {
+            layout.push_commitment(&format!("{}Commitment", input.name), input);
+        }
+
+        layout.push(
+            "proverETHAddress",
+            1,
+            SignalKind::ProverAddress,
+            SignalEncoding::Address,
+            None,
+        );
+        for input in plan
+            .external_inputs
+            .iter()
+            .filter(|input| !input.is_committed)
+        {
+            layout.push_external_input(input);
+        }
+
+        layout
+    }
+
+    /// Builds the layout of a Noir circuit, whose public inputs are the flattened
+    /// elements of the tuple returned by `main`.
+    pub fn noir(plan: &CircuitPlan) -> Self {
+        let mut layout = PublicSignalLayout::default();
+
+        layout.push_field("pubkey_hash", SignalKind::PubkeyHash);
+        layout.push_field(
+            "email_nullifier",
+            SignalKind::EmailNullifier {
+                framework: NullifierFramework::Noir,
+            },
+        );
+        layout.push_field("header_hash_hi", SignalKind::HeaderHashHi);
+        layout.push_field("header_hash_lo", SignalKind::HeaderHashLo);
+        layout.push(
+            "prover_address",
+            1,
+            SignalKind::ProverAddress,
+            SignalEncoding::Address,
+            None,
+        );
+        if plan.enable_header_masking {
+            layout.push(
+                "masked_header",
+                plan.email_header_max_length,
+                SignalKind::MaskedHeader,
+                SignalEncoding::Bytes,
+                Some(plan.email_header_max_length),
+            );
+        }
+        if plan.masks_body() {
+            layout.push(
+                "masked_body",
+                plan.email_body_max_length,
+                SignalKind::MaskedBody,
+                SignalEncoding::Bytes,
+                Some(plan.email_body_max_length),
+            );
+        }
+        for input in &plan.external_inputs {
+            if input.is_committed {
+                layout.push_commitment(&format!("{}_commitment", input.name), input);
+            } else {
+                layout.push_external_input(input);
+            }
+        }
+
+        for output in plan.regex_outputs() {
+            match output {
+                RegexOutput::Hash { regex } => layout.push_regex_hash(
+                    plan,
+                    &format!("{}_packed_hash", regex),
+                    regex,
+                    HashFunction::Pedersen,
+                ),
+                RegexOutput::Capture {
+                    regex,
+                    index,
+                    max_length,
+                    ..
+                } => layout.push(
+                    &format!("{}_capture_{}", regex, index + 1),
+                    // The storage of the BoundedVec followed by its length
+                    max_length + 1,
+                    SignalKind::RegexCapture { regex, index },
+                    SignalEncoding::BoundedBytes,
+                    Some(max_length),
+                ),
+            }
+        }
+
+        layout
+    }
+
+    /// Total number of public signals.
+    pub fn len(&self) -> usize {
+        self.signals
+            .last()
+            .map(|signal| signal.start_idx + signal.width)
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty()
+    }
+
+    pub fn signal(&self, name: &str) -> Option<&PublicSignal> {
+        self.signals.iter().find(|signal| signal.name == name)
+    }
+
+    /// Signals passed into the circuit as public inputs rather than computed as outputs.
+    pub fn public_inputs(&self) -> impl Iterator<Item = &PublicSignal> {
+        self.signals.iter().filter(|signal| {
+            matches!(
+                signal.kind,
+                SignalKind::ProverAddress | SignalKind::ExternalInput
+            )
+        })
+    }
+
+    fn push_field(&mut self, name: &str, kind: SignalKind) {
+        self.push(name, 1, kind, SignalEncoding::Field, None);
+    }
+
+    /// Pushes the hash of a regex, using `default` unless the blueprint chose a hash function.
+    fn push_regex_hash(
+        &mut self,
+        plan: &CircuitPlan,
+        name: &str,
+        regex: String,
+        default: HashFunction,
+    ) {
+        let regex_plan = plan.regex(&regex);
+        let hash_function = regex_plan
+            .map(|r| r.hash_function_or(default))
+            .unwrap_or(default);
+        self.push(
+            name,
+            1,
+            SignalKind::RegexHash {
+                salted: regex_plan.is_some_and(|r| r.is_hash_salted),
+                regex,
+            },
+            hash_function.into(),
+            regex_plan.map(|r| r.max_match_length),
+        );
+    }
+
+    fn push_external_input(&mut self, input: &ExternalInputPlan) {
+        self.push(
+            &input.name,
+            input.packed_width,
+            SignalKind::ExternalInput,
+            input.input_type.into(),
+            (input.input_type == ExternalInputType::String).then_some(input.max_length),
+        );
+    }
+
+    fn push_commitment(&mut self, name: &str, input: &ExternalInputPlan) {
+        self.push(
+            name,
+            1,
+            SignalKind::ExternalInputCommitment {
+                input: input.name.clone(),
+            },
+            SignalEncoding::PoseidonHash,
+            Some(input.max_length),
+        );
+    }
+
+    fn push(
+        &mut self,
+        name: &str,
+        width: usize,
+        kind: SignalKind,
+        encoding: SignalEncoding,
+        max_length: Option<usize>,
+    ) {
+        let start_idx = self.len();
+        self.signals.push(PublicSignal {
+            name: name.to_string(),
+            start_idx,
+            width,
+            kind,
+            encoding,
+            max_length,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto_types::proto_blueprint::{
+        Blueprint, DecomposedRegex, DecomposedRegexPart, ExternalInput,
+    };
+
+    fn blueprint() -> Blueprint {
+        Blueprint {
+            circuit_name: "Test".to_string(),
+            email_header_max_length: 1024,
+            enable_header_masking: true,
+            ignore_body_hash_check: true,
+            decomposed_regexes: vec![DecomposedRegex {
+                name: "subject".to_string(),
+                location: "header".to_string(),
+                max_match_length: 128,
+                is_hashed: Some(false),
+                hash_function: None,
+                is_hash_salted: None,
+                parts: vec![
+                    DecomposedRegexPart {
+                        is_public: Some(true),
+                        regex_def: "[a-z]+".to_string(),
+                        max_length: Some(64),
+                    },
+                    DecomposedRegexPart {
+                        is_public: Some(true),
+                        regex_def: "[0-9]+".to_string(),
+                        max_length: Some(10),
+                    },
+                ],
+            }],
+            external_inputs: vec![ExternalInput {
+                name: "recipient".to_string(),
+                max_length: 42,
+                is_committed: None,
+                input_type: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_circom_layout() {
+        let layout = PublicSignalLayout::circom(&CircuitPlan::from(&blueprint()));
+        let names: Vec<(&str, usize, usize)> = layout
+            .signals
+            .iter()
+            .map(|signal| (signal.name.as_str(), signal.start_idx, signal.width))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("maskedHeader", 0, 1024),
+                ("pubkeyHash", 1024, 1),
+                ("headerHashHi", 1025, 1),
+                ("headerHashLo", 1026, 1),
+                ("subjectPackedOut", 1027, 3),
+                ("subjectPackedOut1", 1030, 1),
+                ("proverETHAddress", 1031, 1),
+                ("recipient", 1032, 2),
+            ]
+        );
+        assert_eq!(layout.len(), 1034);
+        assert_eq!(
+            layout
+                .public_inputs()
+                .map(|signal| signal.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["proverETHAddress", "recipient"]
+        );
+    }
+
+    #[test]
+    fn test_noir_layout() {
+        let mut blueprint = blueprint();
+        blueprint.decomposed_regexes[0].is_hashed = Some(true);
+
+        let layout = PublicSignalLayout::noir(&CircuitPlan::from(&blueprint));
+        let names: Vec<(&str, usize, usize, SignalEncoding)> = layout
+            .signals
+            .iter()
+            .map(|signal| {
+                (
+                    signal.name.as_str(),
+                    signal.start_idx,
+                    signal.width,
+                    signal.encoding,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("pubkey_hash", 0, 1, SignalEncoding::Field),
+                ("email_nullifier", 1, 1, SignalEncoding::Field),
+                ("header_hash_hi", 2, 1, SignalEncoding::Field),
+                ("header_hash_lo", 3, 1, SignalEncoding::Field),
+                ("prover_address", 4, 1, SignalEncoding::Address),
+                ("masked_header", 5, 1024, SignalEncoding::Bytes),
+                ("recipient", 1029, 2, SignalEncoding::PackedBytes),
+                ("subject_packed_hash", 1031, 1, SignalEncoding::PedersenHash),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_committed_external_input() {
+        let mut blueprint = blueprint();
+        blueprint.external_inputs[0].is_committed = Some(true);
+        let plan = CircuitPlan::from(&blueprint);
+
+        let layout = PublicSignalLayout::circom(&plan);
+        let commitment = layout.signal("recipientCommitment").unwrap();
+        assert_eq!(commitment.start_idx, 1031);
+        assert_eq!(commitment.width, 1);
+        assert_eq!(commitment.encoding, SignalEncoding::PoseidonHash);
+        assert_eq!(layout.signal("proverETHAddress").unwrap().start_idx, 1032);
+        assert!(layout.signal("recipient").is_none());
+        assert_eq!(
+            layout
+                .public_inputs()
+                .map(|signal| signal.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["proverETHAddress"]
+        );
+
+        let layout = PublicSignalLayout::noir(&plan);
+        let commitment = layout.signal("recipient_commitment").unwrap();
+        assert_eq!(commitment.start_idx, 1029);
+        assert_eq!(
+            commitment.kind,
+            SignalKind::ExternalInputCommitment {
+                input: "recipient".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_nullifier_framework() {
+        let mut blueprint = blueprint();
+        blueprint.enable_nullifier = Some(true);
+        let plan = CircuitPlan::from(&blueprint);
+
+        assert_eq!(
+            PublicSignalLayout::circom(&plan)
+                .signal("emailNullifier")
+                .unwrap()
+                .kind,
+            SignalKind::EmailNullifier {
+                framework: NullifierFramework::Circom
+            }
+        );
+        assert_eq!(
+            PublicSignalLayout::noir(&plan)
+                .signal("email_nullifier")
+                .unwrap()
+                .kind,
+            SignalKind::EmailNullifier {
+                framework: NullifierFramework::Noir
+            }
+        );
+    }
+}
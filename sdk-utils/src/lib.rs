@@ -1,11 +1,25 @@
 mod blueprint;
 mod command;
+mod decoder;
+#[cfg(feature = "test-support")]
+pub mod fixtures;
+mod layout;
+mod packaging;
+mod plan;
 pub mod proto_types;
 mod storage;
+mod templates;
+mod validation;
 
 pub use blueprint::*;
 pub use command::*;
+pub use decoder::*;
+pub use layout::*;
+pub use packaging::*;
+pub use plan::*;
 pub use storage::*;
+pub use templates::*;
+pub use validation::*;
 
 pub fn compute_signal_length(max_length: usize) -> usize {
     (max_length / 31) + if max_length % 31 != 0 { 1 } else { 0 }
@@ -0,0 +1,213 @@
+use anyhow::{anyhow, Result};
+use flate2::{write::GzEncoder, Compression, GzBuilder};
+use relayer_utils::LOG;
+use slog::info;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use zip::{write::FileOptions, CompressionMethod, DateTime, ZipWriter};
+
+/// Archives larger than this need zip64 records.
+const ZIP64_THRESHOLD: u64 = u32::MAX as u64;
+
+/// Creates a zip archive at `output` containing `entries`, given relative to `base_dir`.
+/// Directories are added recursively, the same way `zip -r` would.
+///
+/// Entries are written in sorted order with a fixed timestamp, so identical inputs
+/// always produce byte-identical archives.
+pub fn zip_paths(
+    base_dir: impl AsRef<Path>,
+    entries: &[&str],
+    output: impl AsRef<Path>,
+) -> Result<()> {
+    let base_dir = base_dir.as_ref();
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = base_dir.join(entry);
+        if !path.exists() {
+            return Err(anyhow!("Cannot zip missing path {}", path.display()));
+        }
+        collect_paths(base_dir, &path, &mut paths)?;
+    }
+
+    write_zip(base_dir, paths, output.as_ref())
+}
+
+/// Creates a zip archive at `output` containing every file below `base_dir` for which
+/// `filter` returns true, similar to `zip -r output . -i <pattern>`.
+pub fn zip_dir_filtered(
+    base_dir: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    filter: impl Fn(&Path) -> bool,
+) -> Result<()> {
+    let base_dir = base_dir.as_ref();
+
+    let mut paths = Vec::new();
+    collect_paths(base_dir, base_dir, &mut paths)?;
+    paths.retain(|path| path.is_file() && filter(path));
+
+    write_zip(base_dir, paths, output.as_ref())
+}
+
+/// Compresses `path` into `<path>.gz` and removes the original, like `gzip <path>`.
+/// The gzip header carries no file name or modification time.
+pub fn gzip_file(path: impl AsRef<Path>) -> Result<PathBuf> {
+    let path = path.as_ref();
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+
+    let mut input = BufReader::new(File::open(path)?);
+    let mut encoder: GzEncoder<BufWriter<File>> = GzBuilder::new().mtime(0).write(
+        BufWriter::new(File::create(&gz_path)?),
+        Compression::default(),
+    );
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.flush()?;
+
+    fs::remove_file(path)?;
+    info!(LOG, "Compressed file"; "path" => gz_path.display().to_string());
+
+    Ok(gz_path)
+}
+
+/// Moves a file, falling back to copy and delete when `from` and `to` are on different filesystems.
+pub fn move_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+fn collect_paths(base_dir: &Path, path: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if path != base_dir {
+        paths.push(path.to_path_buf());
+    }
+
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            collect_paths(base_dir, &entry?.path(), paths)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_zip(base_dir: &Path, mut paths: Vec<PathBuf>, output: &Path) -> Result<()> {
+    paths.sort();
+    paths.dedup();
+
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(output)?));
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default());
+
+    for path in paths {
+        // Never pack the archive into itself when it is written inside `base_dir`
+        if path == output {
+            continue;
+        }
+
+        let name = archive_name(base_dir, &path)?;
+        let metadata = fs::metadata(&path)?;
+        let options = options.unix_permissions(file_mode(&metadata));
+
+        if metadata.is_dir() {
+            zip.add_directory(name, options)?;
+        } else {
+            zip.start_file(name, options.large_file(metadata.len() >= ZIP64_THRESHOLD))?;
+            io::copy(&mut BufReader::new(File::open(&path)?), &mut zip)?;
+        }
+    }
+
+    zip.finish()?.flush()?;
+    info!(LOG, "Created zip archive"; "path" => output.display().to_string());
+
+    Ok(())
+}
+
+fn archive_name(base_dir: &Path, path: &Path) -> Result<String> {
+    let relative = path.strip_prefix(base_dir)?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Ok(parts.join("/"))
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Only keep whether the entry is executable, so umask differences don't leak into archives
+    if metadata.is_dir() || metadata.permissions().mode() & 0o111 != 0 {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    if metadata.is_dir() {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sdk-utils-packaging-{}", name));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_zip_paths_is_deterministic() {
+        let first = scratch_dir("first");
+        let second = scratch_dir("second");
+
+        // Create the same tree in a different order to make sure it does not matter
+        fs::create_dir_all(first.join("regex")).unwrap();
+        fs::write(first.join("regex/a_regex.circom"), "a").unwrap();
+        fs::write(first.join("circuit.circom"), "circuit").unwrap();
+        fs::write(second.join("circuit.circom"), "circuit").unwrap();
+        fs::create_dir_all(second.join("regex")).unwrap();
+        fs::write(second.join("regex/a_regex.circom"), "a").unwrap();
+
+        for dir in [&first, &second] {
+            zip_paths(dir, &["regex", "circuit.circom"], dir.join("out.zip")).unwrap();
+        }
+
+        assert_eq!(
+            fs::read(first.join("out.zip")).unwrap(),
+            fs::read(second.join("out.zip")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gzip_file_is_deterministic() {
+        let dir = scratch_dir("gzip");
+        let mut outputs = Vec::new();
+        for name in ["one", "two"] {
+            fs::write(dir.join(name), "zkey bytes").unwrap();
+            let gz_path = gzip_file(dir.join(name)).unwrap();
+            assert!(!dir.join(name).exists());
+            outputs.push(fs::read(gz_path).unwrap());
+        }
+
+        assert_eq!(outputs[0], outputs[1]);
+    }
+}
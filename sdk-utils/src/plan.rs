@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compute_signal_length,
+    proto_types::proto_blueprint::{self, Blueprint},
+};
+
+/// RSA key size used when the blueprint does not specify one.
+pub const DEFAULT_RSA_KEY_BITS: usize = 2048;
+
+/// Part of the email a regex is matched against.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegexLocation {
+    Header,
+    Body,
+    /// The body after soft line breaks were removed.
+    DecodedBody,
+}
+
+/// Hash function committing to the revealed parts of a hashed regex.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HashFunction {
+    /// `PoseidonModular` over the packed parts, the default of Circom.
+    Poseidon,
+    /// `pedersen_hash` over the packed parts, the default of Noir. Not available in Circom.
+    Pedersen,
+}
+
+/// A public capture group of a regex.
+#[derive(Serialize, Debug, Clone)]
+pub struct CapturePlan {
+    pub max_length: usize,
+    /// Number of field elements the capture occupies when packed 31 bytes per field.
+    pub packed_width: usize,
+}
+
+/// A decomposed regex with everything the renderers derive from it.
+#[derive(Serialize, Debug, Clone)]
+pub struct RegexPlan {
+    pub name: String,
+    pub location: RegexLocation,
+    pub max_length_of_location: usize,
+    pub max_match_length: usize,
+    /// Number of field elements the whole match occupies when packed.
+    pub match_packed_width: usize,
+    pub is_hashed: bool,
+    /// Hash function chosen in the blueprint, `None` to use the default of the framework.
+    pub hash_function: Option<HashFunction>,
+    /// Whether a private salt is hashed after the packed parts.
+    pub is_hash_salted: bool,
+    pub captures: Vec<CapturePlan>,
+}
+
+impl RegexPlan {
+    /// Returns the hash function of the regex, or `default` if the blueprint left it open.
+    pub fn hash_function_or(&self, default: HashFunction) -> HashFunction {
+        self.hash_function.unwrap_or(default)
+    }
+}
+
+/// Solidity type of an external input.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalInputType {
+    /// Bytes packed little-endian, 31 per field element.
+    String,
+    /// A single field element below 2^160.
+    Address,
+    /// A single field element, so values must be below the field modulus.
+    Uint256,
+    /// Two field elements holding the high and low 16 bytes.
+    Bytes32,
+    /// A single field element constrained to 0 or 1.
+    Bool,
+}
+
+impl ExternalInputType {
+    pub fn solidity_type(self) -> &'static str {
+        match self {
+            ExternalInputType::String => "string",
+            ExternalInputType::Address => "address",
+            ExternalInputType::Uint256 => "uint256",
+            ExternalInputType::Bytes32 => "bytes32",
+            ExternalInputType::Bool => "bool",
+        }
+    }
+
+    /// Whether the input is passed as an array of field elements rather than a single one.
+    pub fn is_array(self) -> bool {
+        matches!(self, ExternalInputType::String | ExternalInputType::Bytes32)
+    }
+}
+
+/// An external input passed to the circuit.
+#[derive(Serialize, Debug, Clone)]
+pub struct ExternalInputPlan {
+    pub name: String,
+    pub input_type: ExternalInputType,
+    /// Maximum length in bytes, only used by strings.
+    pub max_length: usize,
+    /// Number of field elements the input occupies.
+    pub packed_width: usize,
+    /// Whether the input is private and only a Poseidon commitment to it and a salt is public.
+    pub is_committed: bool,
+}
+
+/// A value revealed by a regex, either a packed capture group or the hash of all captures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexOutput {
+    Capture {
+        regex: String,
+        /// Zero-based index among the public parts of the regex.
+        index: usize,
+        max_length: usize,
+        packed_width: usize,
+    },
+    Hash {
+        regex: String,
+    },
+}
+
+/// Options for generating the circuit inputs from an email, named after the options
+/// of `generateEmailVerifierInputs` in `@zk-email/helpers`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InputGenerationOptions {
+    pub max_headers_length: usize,
+    pub max_body_length: usize,
+    pub ignore_body_hash_check: bool,
+    pub remove_soft_line_breaks: bool,
+    pub enable_header_masking: bool,
+    pub enable_body_masking: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha_precompute_selector: Option<String>,
+    pub rsa_key_bits: usize,
+}
+
+/// Framework-neutral description of the circuit for a blueprint, computed once
+/// and consumed by the Circom and Noir renderers.
+#[derive(Serialize, Debug, Clone)]
+pub struct CircuitPlan {
+    pub circuit_name: String,
+    pub email_header_max_length: usize,
+    pub email_body_max_length: usize,
+    pub ignore_body_hash_check: bool,
+    pub enable_header_masking: bool,
+    pub enable_body_masking: bool,
+    pub remove_soft_linebreaks: bool,
+    pub rsa_key_bits: usize,
+    pub enable_nullifier: bool,
+    /// Anchor in the body up to which the SHA-256 state is precomputed outside the circuit.
+    pub sha_precompute_selector: Option<String>,
+    pub regexes: Vec<RegexPlan>,
+    pub external_inputs: Vec<ExternalInputPlan>,
+}
+
+impl From<&Blueprint> for CircuitPlan {
+    fn from(blueprint: &Blueprint) -> Self {
+        let email_header_max_length = blueprint.email_header_max_length as usize;
+        let email_body_max_length = blueprint.email_body_max_length as usize;
+
+        let regexes = blueprint
+            .decomposed_regexes
+            .iter()
+            .map(|regex| {
+                let (location, max_length_of_location) = if regex.location == "header" {
+                    (RegexLocation::Header, email_header_max_length)
+                } else if blueprint.remove_soft_linebreaks {
+                    (RegexLocation::DecodedBody, email_body_max_length)
+                } else {
+                    (RegexLocation::Body, email_body_max_length)
+                };
+
+                let captures = regex
+                    .parts
+                    .iter()
+                    .filter(|part| part.is_public == Some(true))
+                    .map(|part| CapturePlan {
+                        max_length: part.max_length() as usize,
+                        packed_width: compute_signal_length(part.max_length() as usize),
+                    })
+                    .collect();
+
+                RegexPlan {
+                    name: regex.name.clone(),
+                    location,
+                    max_length_of_location,
+                    max_match_length: regex.max_match_length as usize,
+                    match_packed_width: compute_signal_length(regex.max_match_length as usize),
+                    is_hashed: regex.is_hashed.unwrap_or(false),
+                    hash_function: match regex.hash_function() {
+                        proto_blueprint::HashFunction::HashFunctionDefault => None,
+                        proto_blueprint::HashFunction::Poseidon => Some(HashFunction::Poseidon),
+                        proto_blueprint::HashFunction::Pedersen => Some(HashFunction::Pedersen),
+                    },
+                    is_hash_salted: regex.is_hash_salted.unwrap_or(false),
+                    captures,
+                }
+            })
+            .collect();
+
+        let external_inputs = blueprint
+            .external_inputs
+            .iter()
+            .map(|input| {
+                let input_type = match input.input_type() {
+                    proto_blueprint::ExternalInputType::ExternalInputTypeString => {
+                        ExternalInputType::String
+                    }
+                    proto_blueprint::ExternalInputType::Address => ExternalInputType::Address,
+                    proto_blueprint::ExternalInputType::Uint256 => ExternalInputType::Uint256,
+                    proto_blueprint::ExternalInputType::Bytes32 => ExternalInputType::Bytes32,
+                    proto_blueprint::ExternalInputType::Bool => ExternalInputType::Bool,
+                };
+                let packed_width = match input_type {
+                    ExternalInputType::String => compute_signal_length(input.max_length as usize),
+                    ExternalInputType::Bytes32 => 2,
+                    _ => 1,
+                };
+                ExternalInputPlan {
+                    name: input.name.clone(),
+                    input_type,
+                    max_length: input.max_length as usize,
+                    packed_width,
+                    is_committed: input.is_committed.unwrap_or(false),
+                }
+            })
+            .collect();
+
+        CircuitPlan {
+            circuit_name: blueprint.circuit_name.clone(),
+            email_header_max_length,
+            email_body_max_length,
+            ignore_body_hash_check: blueprint.ignore_body_hash_check,
+            enable_header_masking: blueprint.enable_header_masking,
+            enable_body_masking: blueprint.enable_body_masking,
+            remove_soft_linebreaks: blueprint.remove_soft_linebreaks,
+            rsa_key_bits: blueprint
+                .rsa_key_bits
+                .map(|bits| bits as usize)
+                .unwrap_or(DEFAULT_RSA_KEY_BITS),
+            enable_nullifier: blueprint.enable_nullifier.unwrap_or(false),
+            sha_precompute_selector: (!blueprint.ignore_body_hash_check
+                && !blueprint.sha_precompute_selector.is_empty())
+            .then(|| blueprint.sha_precompute_selector.clone()),
+            regexes,
+            external_inputs,
+        }
+    }
+}
+
+impl CircuitPlan {
+    /// Whether the circuit outputs the masked body. Like `EmailVerifier` in Circom,
+    /// the body can only be masked when it is part of the circuit.
+    pub fn masks_body(&self) -> bool {
+        self.enable_body_masking && !self.ignore_body_hash_check
+    }
+
+    pub fn input_generation_options(&self) -> InputGenerationOptions {
+        InputGenerationOptions {
+            max_headers_length: self.email_header_max_length,
+            max_body_length: self.email_body_max_length,
+            ignore_body_hash_check: self.ignore_body_hash_check,
+            remove_soft_line_breaks: self.remove_soft_linebreaks,
+            enable_header_masking: self.enable_header_masking,
+            enable_body_masking: self.enable_body_masking,
+            sha_precompute_selector: self.sha_precompute_selector.clone(),
+            rsa_key_bits: self.rsa_key_bits,
+        }
+    }
+
+    pub fn regex(&self, name: &str) -> Option<&RegexPlan> {
+        self.regexes.iter().find(|regex| regex.name == name)
+    }
+
+    /// Returns the values revealed by the regexes in blueprint order. A hashed regex
+    /// reveals a single hash, otherwise each public capture group is revealed.
+    pub fn regex_outputs(&self) -> Vec<RegexOutput> {
+        let mut outputs = Vec::new();
+        for regex in &self.regexes {
+            if regex.captures.is_empty() {
+                continue;
+            }
+            if regex.is_hashed {
+                outputs.push(RegexOutput::Hash {
+                    regex: regex.name.clone(),
+                });
+                continue;
+            }
+            for (index, capture) in regex.captures.iter().enumerate() {
+                outputs.push(RegexOutput::Capture {
+                    regex: regex.name.clone(),
+                    index,
+                    max_length: capture.max_length,
+                    packed_width: capture.packed_width,
+                });
+            }
+        }
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto_types::proto_blueprint::{
+        DecomposedRegex, DecomposedRegexPart, ExternalInput,
+    };
+
+    fn part(is_public: bool, max_length: Option<i32>) -> DecomposedRegexPart {
+        DecomposedRegexPart {
+            is_public: Some(is_public),
+            regex_def: "[a-z]+".to_string(),
+            max_length,
+        }
+    }
+
+    #[test]
+    fn test_circuit_plan() {
+        let blueprint = Blueprint {
+            circuit_name: "Test".to_string(),
+            email_header_max_length: 1024,
+            email_body_max_length: 2048,
+            remove_soft_linebreaks: true,
+            decomposed_regexes: vec![
+                DecomposedRegex {
+                    name: "subject".to_string(),
+                    location: "header".to_string(),
+                    max_match_length: 64,
+                    is_hashed: Some(false),
+                    hash_function: None,
+                    is_hash_salted: None,
+                    parts: vec![
+                        part(false, None),
+                        part(true, Some(40)),
+                        part(true, Some(31)),
+                    ],
+                },
+                DecomposedRegex {
+                    name: "amount".to_string(),
+                    location: "body".to_string(),
+                    max_match_length: 100,
+                    is_hashed: Some(true),
+                    hash_function: None,
+                    is_hash_salted: None,
+                    parts: vec![part(true, Some(20))],
+                },
+            ],
+            external_inputs: vec![
+                ExternalInput {
+                    name: "address".to_string(),
+                    max_length: 44,
+                    is_committed: None,
+                    input_type: None,
+                },
+                ExternalInput {
+                    name: "digest".to_string(),
+                    max_length: 0,
+                    is_committed: None,
+                    input_type: Some(proto_blueprint::ExternalInputType::Bytes32 as i32),
+                },
+                ExternalInput {
+                    name: "recipient".to_string(),
+                    max_length: 0,
+                    is_committed: None,
+                    input_type: Some(proto_blueprint::ExternalInputType::Address as i32),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let plan = CircuitPlan::from(&blueprint);
+
+        assert_eq!(plan.regexes[0].location, RegexLocation::Header);
+        assert_eq!(plan.regexes[0].max_length_of_location, 1024);
+        assert_eq!(plan.regexes[0].captures.len(), 2);
+        assert_eq!(plan.regexes[0].captures[0].packed_width, 2);
+        assert_eq!(plan.regexes[0].captures[1].packed_width, 1);
+        assert_eq!(plan.regexes[1].location, RegexLocation::DecodedBody);
+        assert_eq!(plan.regexes[1].match_packed_width, 4);
+        assert_eq!(plan.external_inputs[0].packed_width, 2);
+        assert_eq!(
+            plan.external_inputs[1].input_type,
+            ExternalInputType::Bytes32
+        );
+        assert_eq!(plan.external_inputs[1].packed_width, 2);
+        assert_eq!(plan.external_inputs[2].packed_width, 1);
+
+        assert_eq!(
+            plan.regex_outputs(),
+            vec![
+                RegexOutput::Capture {
+                    regex: "subject".to_string(),
+                    index: 0,
+                    max_length: 40,
+                    packed_width: 2,
+                },
+                RegexOutput::Capture {
+                    regex: "subject".to_string(),
+                    index: 1,
+                    max_length: 31,
+                    packed_width: 1,
+                },
+                RegexOutput::Hash {
+                    regex: "amount".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+use relayer_utils::LOG;
+use slog::info;
+
+/// Environment variable pointing to a directory of custom templates.
+pub const TEMPLATES_DIR_ENV: &str = "TEMPLATES_DIR";
+
+/// Returns the contents of the template `file_name`, preferring a file of the same
+/// name in `$TEMPLATES_DIR` and falling back to the `embedded` copy compiled into the binary.
+pub fn load_template(file_name: &str, embedded: &str) -> Result<String> {
+    match std::env::var(TEMPLATES_DIR_ENV) {
+        Ok(dir) => load_template_from(Some(PathBuf::from(dir)), file_name, embedded),
+        Err(_) => load_template_from(None, file_name, embedded),
+    }
+}
+
+fn load_template_from(dir: Option<PathBuf>, file_name: &str, embedded: &str) -> Result<String> {
+    let Some(dir) = dir else {
+        return Ok(embedded.to_string());
+    };
+
+    if !dir.is_dir() {
+        return Err(anyhow!(
+            "{} is set to {}, which is not a directory",
+            TEMPLATES_DIR_ENV,
+            dir.display()
+        ));
+    }
+
+    let path = dir.join(file_name);
+    if !path.exists() {
+        return Ok(embedded.to_string());
+    }
+
+    info!(LOG, "Using custom template"; "path" => path.display().to_string());
+    Ok(fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_template_override() {
+        assert_eq!(
+            load_template_from(None, "a.tera", "embedded").unwrap(),
+            "embedded"
+        );
+
+        let dir = std::env::temp_dir().join(format!("sdk-utils-templates-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.tera"), "custom").unwrap();
+
+        assert_eq!(
+            load_template_from(Some(dir.clone()), "a.tera", "embedded").unwrap(),
+            "custom"
+        );
+        assert_eq!(
+            load_template_from(Some(dir.clone()), "b.tera", "embedded").unwrap(),
+            "embedded"
+        );
+        assert!(load_template_from(Some(dir.join("missing")), "a.tera", "embedded").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::{anyhow, Result};
+
+use crate::proto_types::proto_blueprint::{
+    Blueprint, ExternalInputType, HashFunction, ZkFramework,
+};
+
+/// SHA-256 pads to 64 byte blocks, so the circuits require max lengths to be multiples of 64.
+const SHA_BLOCK_SIZE: i32 = 64;
+
+/// DKIM key sizes the circuit templates can be instantiated for. Each size is covered by
+/// `test_compile_circuit_rsa_key_sizes` in both the Circom and the Noir service.
+pub const SUPPORTED_RSA_KEY_BITS: [i32; 3] = [1024, 2048, 4096];
+
+/// Checks that a blueprint can be rendered into a circuit before any files are generated.
+pub fn validate_blueprint(blueprint: &Blueprint) -> Result<()> {
+    if !is_identifier(&blueprint.circuit_name) {
+        return Err(anyhow!(
+            "Invalid circuit name '{}': must be a valid identifier",
+            blueprint.circuit_name
+        ));
+    }
+
+    validate_max_length("email_header_max_length", blueprint.email_header_max_length)?;
+    if !blueprint.ignore_body_hash_check {
+        validate_max_length("email_body_max_length", blueprint.email_body_max_length)?;
+    }
+
+    if let Some(bits) = blueprint.rsa_key_bits {
+        if !SUPPORTED_RSA_KEY_BITS.contains(&bits) {
+            return Err(anyhow!(
+                "Unsupported RSA key size {} bits, expected one of {:?}",
+                bits,
+                SUPPORTED_RSA_KEY_BITS
+            ));
+        }
+    }
+
+    if blueprint.enforce_unique_nullifier == Some(true) && blueprint.enable_nullifier != Some(true)
+    {
+        return Err(anyhow!(
+            "enforce_unique_nullifier requires enable_nullifier to be set"
+        ));
+    }
+
+    validate_sha_precompute_selector(blueprint)?;
+
+    // Domains are rendered into Solidity string literals
+    for domain in &blueprint.additional_sender_domains {
+        if !is_domain(domain) {
+            return Err(anyhow!("Invalid sender domain '{}'", domain));
+        }
+    }
+
+    for regex in &blueprint.decomposed_regexes {
+        if !is_identifier(&regex.name) {
+            return Err(anyhow!(
+                "Invalid regex name '{}': must be a valid identifier",
+                regex.name
+            ));
+        }
+        if regex.location != "header" && regex.location != "body" {
+            return Err(anyhow!(
+                "Invalid location '{}' for regex '{}': expected 'header' or 'body'",
+                regex.location,
+                regex.name
+            ));
+        }
+        if regex.location == "body" && blueprint.ignore_body_hash_check {
+            return Err(anyhow!(
+                "Regex '{}' matches the body, but the body hash check is ignored",
+                regex.name
+            ));
+        }
+        if regex.max_match_length <= 0 {
+            return Err(anyhow!(
+                "max_match_length of regex '{}' must be positive",
+                regex.name
+            ));
+        }
+        if regex.is_hash_salted == Some(true) && regex.is_hashed != Some(true) {
+            return Err(anyhow!(
+                "Regex '{}' is salted, but is_hashed is not set",
+                regex.name
+            ));
+        }
+        // Circom has no Pedersen hash compatible with the one of Noir
+        if regex.hash_function() == HashFunction::Pedersen && targets_circom(blueprint) {
+            return Err(anyhow!(
+                "Regex '{}' uses the Pedersen hash, which is not supported by Circom",
+                regex.name
+            ));
+        }
+        for part in &regex.parts {
+            if part.is_public == Some(true) && part.max_length.is_none() {
+                return Err(anyhow!(
+                    "max_length is required for public regex part '{}' in regex '{}', but was not provided",
+                    part.regex_def,
+                    regex.name
+                ));
+            }
+        }
+    }
+
+    for input in &blueprint.external_inputs {
+        if !is_identifier(&input.name) {
+            return Err(anyhow!(
+                "Invalid external input name '{}': must be a valid identifier",
+                input.name
+            ));
+        }
+        if input.input_type() == ExternalInputType::ExternalInputTypeString && input.max_length <= 0
+        {
+            return Err(anyhow!(
+                "max_length of external input '{}' must be positive",
+                input.name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The selector is searched for in the raw body while generating inputs and rendered
+/// into the circuits, so it must be a single line of printable ASCII that fits the body.
+fn validate_sha_precompute_selector(blueprint: &Blueprint) -> Result<()> {
+    let selector = &blueprint.sha_precompute_selector;
+    if selector.is_empty() {
+        return Ok(());
+    }
+    if blueprint.ignore_body_hash_check {
+        return Err(anyhow!(
+            "sha_precompute_selector is set, but the body hash check is ignored"
+        ));
+    }
+    if !selector.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+        return Err(anyhow!(
+            "sha_precompute_selector must be printable ASCII on a single line"
+        ));
+    }
+    if selector.len() > blueprint.email_body_max_length as usize {
+        return Err(anyhow!(
+            "sha_precompute_selector is longer than email_body_max_length ({})",
+            blueprint.email_body_max_length
+        ));
+    }
+    Ok(())
+}
+
+fn targets_circom(blueprint: &Blueprint) -> bool {
+    blueprint.client_zk_framework() == ZkFramework::Circom
+        || blueprint.server_zk_framework() == ZkFramework::Circom
+}
+
+fn validate_max_length(field: &str, value: i32) -> Result<()> {
+    if value <= 0 || value % SHA_BLOCK_SIZE != 0 {
+        return Err(anyhow!(
+            "{} must be a positive multiple of {}, got {}",
+            field,
+            SHA_BLOCK_SIZE,
+            value
+        ));
+    }
+    Ok(())
+}
+
+fn is_domain(domain: &str) -> bool {
+    !domain.is_empty()
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto_types::proto_blueprint::{
+        DecomposedRegex, DecomposedRegexPart, ExternalInput,
+    };
+
+    fn blueprint() -> Blueprint {
+        Blueprint {
+            circuit_name: "Test".to_string(),
+            email_header_max_length: 1024,
+            email_body_max_length: 0,
+            ignore_body_hash_check: true,
+            decomposed_regexes: vec![DecomposedRegex {
+                name: "Subject".to_string(),
+                location: "header".to_string(),
+                max_match_length: 64,
+                is_hashed: None,
+                hash_function: None,
+                is_hash_salted: None,
+                parts: vec![DecomposedRegexPart {
+                    is_public: Some(true),
+                    regex_def: "[a-z]+".to_string(),
+                    max_length: Some(20),
+                }],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_blueprint() {
+        assert!(validate_blueprint(&blueprint()).is_ok());
+
+        let mut invalid = blueprint();
+        invalid.email_header_max_length = 1000;
+        assert!(validate_blueprint(&invalid).is_err());
+
+        let mut invalid = blueprint();
+        invalid.decomposed_regexes[0].location = "body".to_string();
+        assert!(validate_blueprint(&invalid).is_err());
+
+        let mut invalid = blueprint();
+        invalid.decomposed_regexes[0].parts[0].max_length = None;
+        assert!(validate_blueprint(&invalid).is_err());
+
+        for bits in [1024, 2048, 4096] {
+            let mut valid = blueprint();
+            valid.rsa_key_bits = Some(bits);
+            assert!(validate_blueprint(&valid).is_ok());
+        }
+
+        for bits in [512, 3072] {
+            let mut invalid = blueprint();
+            invalid.rsa_key_bits = Some(bits);
+            assert!(validate_blueprint(&invalid).is_err());
+        }
+
+        let mut invalid = blueprint();
+        invalid.enforce_unique_nullifier = Some(true);
+        assert!(validate_blueprint(&invalid).is_err());
+
+        let mut invalid = blueprint();
+        invalid.sha_precompute_selector = "<div id=\"body\">".to_string();
+        assert!(validate_blueprint(&invalid).is_err());
+
+        let mut valid = blueprint();
+        valid.ignore_body_hash_check = false;
+        valid.email_body_max_length = 1024;
+        valid.sha_precompute_selector = "<div id=\"body\">".to_string();
+        assert!(validate_blueprint(&valid).is_ok());
+
+        valid.sha_precompute_selector = "line\nbreak".to_string();
+        assert!(validate_blueprint(&valid).is_err());
+
+        let mut invalid = blueprint();
+        invalid.decomposed_regexes[0].is_hash_salted = Some(true);
+        assert!(validate_blueprint(&invalid).is_err());
+
+        let mut valid = blueprint();
+        valid.decomposed_regexes[0].hash_function = Some(HashFunction::Pedersen as i32);
+        valid.client_zk_framework = ZkFramework::Noir as i32;
+        assert!(validate_blueprint(&valid).is_ok());
+
+        valid.server_zk_framework = ZkFramework::Circom as i32;
+        assert!(validate_blueprint(&valid).is_err());
+
+        let mut invalid = blueprint();
+        invalid.external_inputs = vec![ExternalInput {
+            name: "recipient".to_string(),
+            ..Default::default()
+        }];
+        assert!(validate_blueprint(&invalid).is_err());
+
+        let mut valid = blueprint();
+        valid.additional_sender_domains = vec!["twitter.com".to_string()];
+        assert!(validate_blueprint(&valid).is_ok());
+
+        valid.additional_sender_domains = vec!["x.com\"".to_string()];
+        assert!(validate_blueprint(&valid).is_err());
+
+        // Only strings need a max length
+        let mut valid = invalid;
+        valid.external_inputs[0].input_type = Some(ExternalInputType::Address as i32);
+        assert!(validate_blueprint(&valid).is_ok());
+    }
+}